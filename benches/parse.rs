@@ -0,0 +1,107 @@
+//! Parsing benchmarks on large generated declarations.
+//!
+//! Run with `cargo bench`. Timings are measured by criterion. Since they are
+//! noisy, the number of heap allocations of a single parse is printed too.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use proc_macro2::TokenStream;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn big_enum(variant_count: usize) -> TokenStream {
+    let mut source = String::from("#[derive(Clone, Debug)] pub enum Big {");
+    for i in 0..variant_count {
+        match i % 4 {
+            0 => source += &format!("Unit{},", i),
+            1 => source += &format!("Tuple{}(u8, Vec<Option<u32>>, HashMap<String, u64>),", i),
+            2 => {
+                source += &format!(
+                    "Named{} {{ a: u8, b: std::vec::Vec<u8>, c: &'static str }},",
+                    i
+                )
+            }
+            _ => source += &format!("/// Doc comment\n#[attr] Value{} = {},", i, i),
+        }
+    }
+    source += "}";
+    source.parse().unwrap()
+}
+
+fn wide_struct(field_count: usize) -> TokenStream {
+    let mut source = String::from("#[derive(Clone, Debug)] pub struct Wide {");
+    for i in 0..field_count {
+        match i % 3 {
+            0 => source += &format!("field_{}: u8,", i),
+            1 => source += &format!("pub field_{}: Vec<Option<u32>>,", i),
+            _ => source += &format!("#[attr] field_{}: &'static str,", i),
+        }
+    }
+    source += "}";
+    source.parse().unwrap()
+}
+
+/// Prints the number of heap allocations made by parsing `tokens` once.
+fn report_allocations(name: &str, tokens: &TokenStream) {
+    let tokens = tokens.clone();
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let declaration = venial::parse_declaration(tokens).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    drop(declaration);
+
+    println!("{:<24} {:>8} allocations", name, allocations);
+}
+
+fn bench_sizes(
+    c: &mut Criterion,
+    group_name: &str,
+    sizes: &[usize],
+    generate: fn(usize) -> TokenStream,
+) {
+    let mut group = c.benchmark_group(group_name);
+    for &size in sizes {
+        let tokens = generate(size);
+        report_allocations(&format!("{}/{}", group_name, size), &tokens);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &tokens, |b, tokens| {
+            b.iter_batched(
+                || tokens.clone(),
+                |tokens| venial::parse_declaration(tokens).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn parse_enum(c: &mut Criterion) {
+    bench_sizes(c, "parse_enum", &[10, 1000, 10000], big_enum);
+}
+
+fn parse_struct(c: &mut Criterion) {
+    bench_sizes(c, "parse_struct", &[10, 1000, 10000], wide_struct);
+}
+
+criterion_group!(benches, parse_enum, parse_struct);
+criterion_main!(benches);
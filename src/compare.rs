@@ -0,0 +1,65 @@
+use proc_macro2::{TokenStream, TokenTree};
+
+/// Returns true if both token streams contain the same tokens.
+///
+/// Spans and punctuation spacing are ignored, so tokens created with `quote!`
+/// compare equal to tokens parsed from a string. Groups are compared by
+/// delimiter and content, literals by their representation.
+///
+/// ```
+/// # use venial::tokens_equal;
+/// # use quote::quote;
+/// let parsed = "struct Hello<T>(Vec<T>);".parse().unwrap();
+/// assert!(tokens_equal(&quote!(struct Hello<T>(Vec<T>);), &parsed));
+/// assert!(!tokens_equal(&quote!(struct Hello<T>(T);), &parsed));
+/// ```
+pub fn tokens_equal(a: &TokenStream, b: &TokenStream) -> bool {
+    let mut a = a.clone().into_iter();
+    let mut b = b.clone().into_iter();
+    loop {
+        match (a.next(), b.next()) {
+            (Some(a), Some(b)) => {
+                if !token_trees_equal(&a, &b) {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Asserts that both token streams contain the same tokens.
+///
+/// See [`tokens_equal`] for how tokens are compared.
+///
+/// ```
+/// # use venial::assert_tokens_eq;
+/// # use quote::quote;
+/// assert_tokens_eq(quote!(a: Vec<u8>), "a : Vec<u8>".parse().unwrap());
+/// ```
+///
+/// # Panics
+///
+/// Panics if the tokens are different, printing both token streams.
+#[track_caller]
+pub fn assert_tokens_eq(a: TokenStream, b: TokenStream) {
+    assert!(
+        tokens_equal(&a, &b),
+        "token streams are not equal\n  left: `{}`\n right: `{}`",
+        a,
+        b
+    );
+}
+
+fn token_trees_equal(a: &TokenTree, b: &TokenTree) -> bool {
+    match (a, b) {
+        (TokenTree::Group(a), TokenTree::Group(b)) => {
+            a.delimiter() == b.delimiter() && tokens_equal(&a.stream(), &b.stream())
+        }
+        (TokenTree::Ident(a), TokenTree::Ident(b)) => a == b,
+        (TokenTree::Punct(a), TokenTree::Punct(b)) => a.as_char() == b.as_char(),
+        (TokenTree::Literal(a), TokenTree::Literal(b)) => a.to_string() == b.to_string(),
+        _ => false,
+    }
+}
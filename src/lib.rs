@@ -53,15 +53,17 @@
 //! ```
 
 // Implementation guidelines and naming conventions:
-// - `parse_xxx` functions return `T`.
-//   - They expect the whole item to be present, and panic otherwise.
-// - `consume_xxx` functions return Option<T>:
+// - `parse_xxx` functions return `Result<T, Error>`.
+//   - They expect the whole item to be present, and return an error otherwise.
+// - `consume_xxx` functions return `Option<T>`, or `Result<Option<T>, Error>` when they can fail:
 //   - None if the syntax item is absent
-//   - panic if the beginning of the syntax item is present but the expect continuation isn't.
+//   - an error if the beginning of the syntax item is present but the expected continuation isn't.
+// - Errors are located at the offending token, with `Error::new_at_span`.
 
 #[cfg(test)]
 mod tests;
 
+mod compare;
 mod error;
 mod parse;
 mod parse_fn;
@@ -70,10 +72,14 @@ mod parse_mod;
 mod parse_type;
 mod parse_utils;
 mod punctuated;
+#[cfg(feature = "serde")]
+mod serde_impls;
 mod types;
 mod types_edition;
 
+pub use compare::{assert_tokens_eq, tokens_equal};
 pub use error::Error;
-pub use parse::parse_declaration;
+pub use parse::{parse_declaration, parse_declaration_str, parse_declarations};
 pub use punctuated::Punctuated;
 pub use types::*;
+pub use types_edition::HasGenerics;
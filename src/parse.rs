@@ -2,7 +2,7 @@ use crate::error::Error;
 use crate::parse_impl::{
     consume_either_fn_type_const_static_impl, parse_const_or_static, parse_impl, parse_trait,
 };
-use crate::parse_mod::{parse_mod, parse_use_declaration};
+use crate::parse_mod::{parse_macro_def, parse_mod, parse_use_declaration};
 use crate::parse_type::{
     consume_declaration_name, consume_generic_params, consume_where_clause, parse_enum_variants,
     parse_named_fields, parse_tuple_fields,
@@ -13,22 +13,13 @@ use crate::types_edition::GroupSpan;
 use proc_macro2::token_stream::IntoIter;
 use proc_macro2::{Delimiter, TokenStream, TokenTree};
 use std::iter::Peekable;
-
-// TODO - Return Result<...>, handle case where TokenStream is valid declaration,
-// but not a type or function.
+use std::str::FromStr;
 
 /// Parses the token stream of a type declaration.
 ///
 /// For instance, if you're implementing a derive macro, you can pass the
 /// token stream as-is.
 ///
-/// ## Panics
-///
-/// Panics if given a token stream that doesn't parse as a valid Rust
-/// type declaration. If the token stream is from an attribute or a derive
-/// macro, there should be no way for this to happen, as Rust will emit an
-/// error instead of calling this macro.
-///
 /// ## Example
 ///
 /// ```
@@ -45,7 +36,13 @@ use std::iter::Peekable;
 ///
 /// ## Errors
 ///
-/// Venial doesn't support enum discriminants with multiple non-grouped tokens. Eg:
+/// Returns an error if the token stream doesn't start with a declaration
+/// keyword venial knows about, if the body of a struct, enum or union
+/// isn't what the keyword calls for, or if an attribute in that body isn't
+/// followed by a field or variant. The error is located at the offending
+/// token, so it can be returned to the user with [`Error::to_compile_error`].
+///
+/// Venial also doesn't support enum discriminants with multiple non-grouped tokens. Eg:
 ///
 /// ```rust
 /// # #[cfg(FALSE)]
@@ -57,16 +54,74 @@ use std::iter::Peekable;
 ///     E = (FOO + BAR),  // Ok
 /// }
 /// ```
-
 pub fn parse_declaration(tokens: TokenStream) -> Result<Declaration, Error> {
     let mut tokens = tokens.into_iter().peekable();
     parse_declaration_tokens(&mut tokens)
 }
 
+/// Parses a token stream made of several declarations, eg the contents of a module.
+///
+/// Declarations are returned in the order they appear in.
+///
+/// ## Example
+///
+/// ```
+/// # use venial::{parse_declarations, Declaration};
+/// # use quote::quote;
+/// let declarations = parse_declarations(quote!(
+///     struct Hello;
+///     fn world() {}
+/// )).unwrap();
+/// assert!(matches!(declarations[0], Declaration::Struct(_)));
+/// assert!(matches!(declarations[1], Declaration::Function(_)));
+/// ```
+///
+/// ## Errors
+///
+/// Returns the error of the first declaration that fails to parse.
+/// See [`parse_declaration`] for details.
+pub fn parse_declarations(tokens: TokenStream) -> Result<Vec<Declaration>, Error> {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut declarations = vec![];
+
+    loop {
+        if tokens.peek().is_none() {
+            break;
+        }
+        declarations.push(parse_declaration_tokens(&mut tokens)?);
+    }
+
+    Ok(declarations)
+}
+
+/// Lexes a string of Rust source and parses it as a declaration.
+///
+/// This is convenient for tools and tests that read Rust code from a file
+/// rather than receive it from the compiler.
+///
+/// ## Example
+///
+/// ```
+/// # use venial::{parse_declaration_str, Declaration};
+/// let struct_type = parse_declaration_str("struct Hello(Foo, Bar);");
+/// assert!(matches!(struct_type, Ok(Declaration::Struct(_))));
+/// ```
+///
+/// ## Errors
+///
+/// Returns an error if the string can't be lexed into tokens, eg because of
+/// unbalanced delimiters or unterminated literals. Otherwise, returns the same
+/// errors as [`parse_declaration`].
+pub fn parse_declaration_str(source: &str) -> Result<Declaration, Error> {
+    let tokens = TokenStream::from_str(source)
+        .map_err(|error| Error::new(format!("cannot lex declaration: {}", error)))?;
+    parse_declaration(tokens)
+}
+
 pub(crate) fn parse_declaration_tokens(
     tokens: &mut Peekable<IntoIter>,
 ) -> Result<Declaration, Error> {
-    let attributes = consume_outer_attributes(tokens);
+    let attributes = consume_outer_attributes(tokens)?;
     let vis_marker = consume_vis_marker(tokens);
 
     let declaration = match tokens.peek().cloned() {
@@ -74,33 +129,43 @@ pub(crate) fn parse_declaration_tokens(
             // struct keyword
             tokens.next().unwrap();
 
-            let struct_name = consume_declaration_name(tokens);
-            let generic_params = consume_generic_params(tokens);
-            let mut where_clause = consume_where_clause(tokens);
+            let struct_name = consume_declaration_name(tokens)?;
+            let generic_params = consume_generic_params(tokens)?;
+            let mut where_clause = consume_where_clause(tokens)?;
 
-            let struct_fields = match tokens
-                .peek()
-                .expect("cannot parse struct: missing body or semicolon")
-            {
-                TokenTree::Punct(punct) if punct.as_char() == ';' => StructFields::Unit,
-                TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+            let struct_fields = match tokens.peek() {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => StructFields::Unit,
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
                     let group = group.clone();
                     // Consume group
                     tokens.next();
-                    StructFields::Tuple(parse_tuple_fields(group))
+                    StructFields::Tuple(parse_tuple_fields(group, &mut Vec::new())?)
                 }
-                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
                     let group = group.clone();
                     // Consume group
                     tokens.next();
-                    StructFields::Named(parse_named_fields(group))
+                    StructFields::Named(parse_named_fields(group, &mut Vec::new())?)
+                }
+                Some(token) => {
+                    return Err(Error::new_at_span(
+                        token.span(),
+                        format!("cannot parse struct: unexpected token {:?}", token),
+                    ));
+                }
+                None => {
+                    return Err(Error::new("cannot parse struct: missing body or semicolon"));
                 }
-                token => panic!("cannot parse struct: unexpected token {:?}", token),
             };
 
             if matches!(struct_fields, StructFields::Tuple(_)) {
-                assert!(where_clause.is_none());
-                where_clause = consume_where_clause(tokens);
+                if let Some(where_clause) = where_clause {
+                    return Err(Error::new_at_tokens(
+                        where_clause,
+                        "cannot parse tuple struct: where clause must come after the fields",
+                    ));
+                }
+                where_clause = consume_where_clause(tokens)?;
             }
 
             let semicolon = consume_punct(tokens, ';');
@@ -120,15 +185,21 @@ pub(crate) fn parse_declaration_tokens(
             // enum keyword
             tokens.next().unwrap();
 
-            let enum_name = consume_declaration_name(tokens);
-            let generic_params = consume_generic_params(tokens);
-            let where_clause = consume_where_clause(tokens);
+            let enum_name = consume_declaration_name(tokens)?;
+            let generic_params = consume_generic_params(tokens)?;
+            let where_clause = consume_where_clause(tokens)?;
 
-            let (group, enum_variants) = match tokens.next().unwrap() {
-                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
-                    (group.clone(), parse_enum_variants(group.stream()))
+            let (tk_braces, enum_variants) = match tokens.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    (GroupSpan::new(&group), parse_enum_variants(group.stream()))
+                }
+                Some(token) => {
+                    return Err(Error::new_at_span(
+                        token.span(),
+                        format!("cannot parse enum: unexpected token {:?}", token),
+                    ));
                 }
-                token => panic!("cannot parse enum: unexpected token {:?}", token),
+                None => return Err(Error::new("cannot parse enum: missing body")),
             };
 
             Declaration::Enum(Enum {
@@ -138,7 +209,7 @@ pub(crate) fn parse_declaration_tokens(
                 name: enum_name,
                 generic_params,
                 where_clause,
-                tk_braces: GroupSpan::new(&group),
+                tk_braces,
                 variants: enum_variants?,
             })
         }
@@ -146,15 +217,21 @@ pub(crate) fn parse_declaration_tokens(
             // union keyword
             tokens.next().unwrap();
 
-            let union_name = consume_declaration_name(tokens);
-            let generic_params = consume_generic_params(tokens);
-            let where_clause = consume_where_clause(tokens);
+            let union_name = consume_declaration_name(tokens)?;
+            let generic_params = consume_generic_params(tokens)?;
+            let where_clause = consume_where_clause(tokens)?;
 
-            let union_fields = match tokens.next().unwrap() {
-                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
-                    parse_named_fields(group)
+            let union_fields = match tokens.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    parse_named_fields(group, &mut Vec::new())?
+                }
+                Some(token) => {
+                    return Err(Error::new_at_span(
+                        token.span(),
+                        format!("cannot parse union: unexpected token {:?}", token),
+                    ));
                 }
-                token => panic!("cannot parse union: unexpected token {:?}", token),
+                None => return Err(Error::new("cannot parse union: missing body")),
             };
 
             Declaration::Union(Union {
@@ -168,27 +245,37 @@ pub(crate) fn parse_declaration_tokens(
             })
         }
         Some(TokenTree::Ident(keyword)) if keyword == "mod" => {
-            let mod_decl = parse_mod(tokens, attributes, vis_marker);
+            let mod_decl = parse_mod(tokens, attributes, vis_marker)?;
             Declaration::Module(mod_decl)
         }
         Some(TokenTree::Ident(keyword)) if keyword == "trait" => {
-            let trait_decl = parse_trait(tokens, attributes, vis_marker);
+            let trait_decl = parse_trait(tokens, attributes, vis_marker)?;
             Declaration::Trait(trait_decl)
         }
         Some(TokenTree::Ident(keyword)) if keyword == "impl" => {
-            let impl_decl = parse_impl(tokens, attributes);
+            let impl_decl = parse_impl(tokens, attributes)?;
             Declaration::Impl(impl_decl)
         }
         Some(TokenTree::Ident(keyword)) if keyword == "static" => {
-            let static_decl = parse_const_or_static(tokens, attributes, vis_marker);
+            let static_decl = parse_const_or_static(tokens, attributes, vis_marker)?;
             Declaration::Constant(static_decl)
         }
         // Note: fn qualifiers appear always in this order in Rust: default const async unsafe extern fn
         Some(TokenTree::Ident(keyword)) if keyword == "use" => {
-            let use_decl = parse_use_declaration(tokens, attributes, vis_marker);
+            let use_decl = parse_use_declaration(tokens, attributes, vis_marker)?;
 
             Declaration::Use(use_decl)
         }
+        Some(TokenTree::Ident(keyword)) if keyword == "macro_rules" => {
+            if let Some(vis_marker) = vis_marker {
+                return Err(Error::new_at_tokens(
+                    vis_marker,
+                    "cannot parse macro definition: `macro_rules!` cannot have a visibility",
+                ));
+            }
+            let macro_decl = parse_macro_def(tokens, attributes)?;
+            Declaration::MacroDef(macro_decl)
+        }
         // Note: fn qualifiers appear always in this order in Rust
         Some(TokenTree::Ident(keyword))
             if matches!(
@@ -197,16 +284,21 @@ pub(crate) fn parse_declaration_tokens(
             ) =>
         {
             // Reuse impl parsing
-            consume_either_fn_type_const_static_impl(tokens, attributes, vis_marker, "declaration")
+            consume_either_fn_type_const_static_impl(tokens, attributes, vis_marker, "declaration")?
         }
         Some(token) => {
-            panic!(
-                "cannot parse declaration: expected keyword struct/enum/union/type/trait/impl/mod/default/const/async/unsafe/extern/fn/static, found token {:?}",
-                token
-            );
+            return Err(Error::new_at_span(
+                token.span(),
+                format!(
+                    "cannot parse declaration: expected keyword struct/enum/union/type/trait/impl/mod/default/const/async/unsafe/extern/fn/static/use/macro_rules, found token {:?}",
+                    token
+                ),
+            ));
         }
         None => {
-            panic!("cannot parse type: expected keyword struct/enum/union/type/trait/impl/mod/default/const/async/unsafe/extern/fn/static, found end-of-stream");
+            return Err(Error::new(
+                "cannot parse declaration: expected keyword struct/enum/union/type/trait/impl/mod/default/const/async/unsafe/extern/fn/static/use/macro_rules, found end-of-stream",
+            ));
         }
     };
     Ok(declaration)
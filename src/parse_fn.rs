@@ -1,9 +1,10 @@
+use crate::error::Error;
 use crate::parse_type::{
     consume_declaration_name, consume_field_type, consume_generic_params, consume_where_clause,
 };
 use crate::parse_utils::{
-    consume_comma, consume_ident, consume_outer_attributes, consume_punct, consume_stuff_until,
-    parse_any_ident, parse_punct,
+    consume_comma, consume_ident, consume_outer_attributes, consume_punct, parse_any_ident,
+    parse_punct, parse_stuff_until, unexpected_token_error,
 };
 use crate::punctuated::Punctuated;
 use crate::types::{
@@ -16,12 +17,14 @@ use std::iter::Peekable;
 type TokenIter = Peekable<proc_macro2::token_stream::IntoIter>;
 
 /// If venial fails to parse the declaration as a function, it can detect that it
-/// is either a constant (`const` ambiguity), an impl or a module (`unsafe` ambiguity).
+/// is either a constant (`const` ambiguity), an impl or a module (`unsafe` ambiguity),
+/// or an extern block (`extern` ambiguity).
 pub(crate) enum NotFunction {
     Const,
     Trait,
     Impl,
     Mod,
+    ExternBlock,
 }
 
 pub(crate) fn consume_fn_qualifiers(tokens: &mut TokenIter) -> FnQualifiers {
@@ -63,7 +66,7 @@ pub(crate) fn consume_fn_qualifiers(tokens: &mut TokenIter) -> FnQualifiers {
     }
 }
 
-fn parse_fn_params(tokens: TokenStream) -> Punctuated<FnParam> {
+fn parse_fn_params(tokens: TokenStream) -> Result<Punctuated<FnParam>, Error> {
     let mut fields = Punctuated::new();
 
     let mut tokens = tokens.into_iter().peekable();
@@ -71,32 +74,40 @@ fn parse_fn_params(tokens: TokenStream) -> Punctuated<FnParam> {
         if tokens.peek().is_none() {
             break;
         }
-        let attributes = consume_outer_attributes(&mut tokens);
+        let attributes = consume_outer_attributes(&mut tokens)?;
 
         let tk_ref = consume_punct(&mut tokens, '&');
         let tk_mut = consume_ident(&mut tokens, "mut");
         let tk_self = consume_ident(&mut tokens, "self");
 
-        let param = if let Some(tk_self) = tk_self {
-            FnParam::Receiver(FnReceiverParam {
+        // `self: Type` is a typed param, the other forms of `self` are receivers
+        let is_typed_self = tk_ref.is_none()
+            && matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == ':');
+
+        let param = match tk_self {
+            Some(tk_self) if !is_typed_self => FnParam::Receiver(FnReceiverParam {
                 attributes,
                 tk_ref,
                 tk_mut,
                 tk_self,
-            })
-        } else {
-            // TODO - handle non-ident argument names
-            let param_name = parse_any_ident(&mut tokens, "fn param name");
-            let tk_colon = parse_punct(&mut tokens, ':', "fn params");
+            }),
+            tk_self => {
+                // TODO - handle non-ident argument names
+                let param_name = match tk_self {
+                    Some(tk_self) => tk_self,
+                    None => parse_any_ident(&mut tokens, "fn param name")?,
+                };
+                let tk_colon = parse_punct(&mut tokens, ':', "fn params")?;
 
-            let ty_tokens = consume_field_type(&mut tokens);
-            FnParam::Typed(FnTypedParam {
-                attributes,
-                tk_mut,
-                name: param_name,
-                tk_colon,
-                ty: TyExpr { tokens: ty_tokens },
-            })
+                let ty_tokens = consume_field_type(&mut tokens)?;
+                FnParam::Typed(FnTypedParam {
+                    attributes,
+                    tk_mut,
+                    name: param_name,
+                    tk_colon,
+                    ty: TyExpr { tokens: ty_tokens },
+                })
+            }
         };
 
         let comma = consume_comma(&mut tokens);
@@ -104,44 +115,43 @@ fn parse_fn_params(tokens: TokenStream) -> Punctuated<FnParam> {
         fields.push(param, comma);
     }
 
-    fields
+    Ok(fields)
 }
 
-fn consume_fn_return(tokens: &mut TokenIter) -> Option<([Punct; 2], TyExpr)> {
-    let dash = consume_punct(tokens, '-')?;
-
-    let tip = match tokens.next() {
-        Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => punct,
-        _ => panic!("cannot parse fn return: expected '>' after '-' token"),
+fn consume_fn_return(tokens: &mut TokenIter) -> Result<Option<([Punct; 2], TyExpr)>, Error> {
+    let dash = match consume_punct(tokens, '-') {
+        Some(dash) => dash,
+        None => return Ok(None),
     };
 
-    Some((
-        [dash, tip],
-        TyExpr {
-            tokens: (consume_stuff_until(
-                tokens,
-                |token| match token {
-                    TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => true,
-                    TokenTree::Ident(i) if i == &Ident::new("where", i.span()) => true,
-                    TokenTree::Punct(punct) if punct.as_char() == ';' => true,
-                    _ => false,
-                },
-                true,
-            )),
+    let tip = parse_punct(tokens, '>', "fn return")?;
+
+    let ty_tokens = parse_stuff_until(
+        tokens,
+        |token| match token {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => true,
+            TokenTree::Ident(i) if i == &Ident::new("where", i.span()) => true,
+            TokenTree::Punct(punct) if punct.as_char() == ';' => true,
+            _ => false,
         },
-    ))
+        "fn return",
+    )?;
+
+    Ok(Some(([dash, tip], TyExpr { tokens: ty_tokens })))
 }
 
 /// Tries to parse a function definition.
 ///
-/// Panics when the following tokens do not constitute a function definition, with one exception:
-/// when `const` is followed by an identifier which is not `fn`, then `None` is returned. This is to
-/// allow fallback to a constant declaration (both can begin with the `const` token).
+/// Returns an error when the following tokens do not constitute a function definition, with
+/// one exception: when the qualifiers are followed by something else than `fn` that they can
+/// also start (eg `const X` or `unsafe impl`), then `Ok(Err(NotFunction))` is returned and
+/// `tokens` is rolled back. This is to allow fallback to eg a constant declaration (both can
+/// begin with the `const` token).
 pub(crate) fn consume_fn(
     tokens: &mut TokenIter,
     attributes: Vec<Attribute>,
     vis_marker: Option<VisMarker>,
-) -> Result<Function, NotFunction> {
+) -> Result<Result<Function, NotFunction>, Error> {
     // TODO consider multiple-lookahead instead of potentially cloning many tokens
     let before_start = tokens.clone();
     let qualifiers = consume_fn_qualifiers(tokens);
@@ -161,10 +171,13 @@ pub(crate) fn consume_fn(
                     Some(TokenTree::Ident(ident)) if ident == "trait" => NotFunction::Trait,
                     Some(TokenTree::Ident(ident)) if ident == "impl" => NotFunction::Impl,
                     Some(TokenTree::Ident(ident)) if ident == "mod" => NotFunction::Mod,
-                    token => panic!(
-                        "expected one of 'fn|trait|impl|mod' after 'unsafe', got {:?}",
-                        token
-                    ),
+                    token => {
+                        return Err(unexpected_token_error(
+                            token.as_ref(),
+                            "declaration",
+                            "one of 'fn|trait|impl|mod' after 'unsafe'",
+                        ))
+                    }
                 }
             } else {
                 unreachable!()
@@ -172,41 +185,66 @@ pub(crate) fn consume_fn(
 
             // rollback iterator, could be start of const declaration
             *tokens = before_start;
-            return Err(declaration_type);
+            return Ok(Err(declaration_type));
         } else {
-            panic!("expected 'fn' keyword, got ident '{}'", ident)
+            return Err(unexpected_token_error(
+                next_token.as_ref(),
+                "function",
+                "'fn' keyword",
+            ));
         }
+    } else if qualifiers.is_extern_block_prefix()
+        && matches!(&next_token, Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace)
+    {
+        // rollback iterator, extern block parsing starts from the qualifiers
+        *tokens = before_start;
+        return Ok(Err(NotFunction::ExternBlock));
     } else {
-        panic!("expected 'fn' keyword")
+        return Err(unexpected_token_error(
+            next_token.as_ref(),
+            "function",
+            "'fn' keyword",
+        ));
     };
 
-    let fn_name = consume_declaration_name(tokens);
-    let generic_params = consume_generic_params(tokens);
+    let fn_name = consume_declaration_name(tokens)?;
+    let generic_params = consume_generic_params(tokens)?;
 
-    let (params, tk_params_parens) = match tokens.next().unwrap() {
-        TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
-            (parse_fn_params(group.stream()), GroupSpan::new(&group))
+    let (params, tk_params_parens) = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            (parse_fn_params(group.stream())?, GroupSpan::new(&group))
+        }
+        token => {
+            return Err(unexpected_token_error(
+                token.as_ref(),
+                "function",
+                "parameter list",
+            ))
         }
-        _ => panic!("cannot parse function"),
     };
 
-    let (tk_return_arrow, return_ty) = if let Some((arrow, ty)) = consume_fn_return(tokens) {
-        (Some(arrow), Some(ty))
-    } else {
-        (None, None)
+    let (tk_return_arrow, return_ty) = match consume_fn_return(tokens)? {
+        Some((arrow, ty)) => (Some(arrow), Some(ty)),
+        None => (None, None),
     };
 
-    let where_clause = consume_where_clause(tokens);
+    let where_clause = consume_where_clause(tokens)?;
 
-    let (function_body, tk_semicolon) = match &tokens.next().unwrap() {
-        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
-            (Some(group.clone()), None)
+    let (function_body, tk_semicolon) = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            (Some(group), None)
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => (None, Some(punct)),
+        token => {
+            return Err(unexpected_token_error(
+                token.as_ref(),
+                "function",
+                "body or ';'",
+            ))
         }
-        TokenTree::Punct(punct) if punct.as_char() == ';' => (None, Some(punct.clone())),
-        _ => panic!("cannot parse function"),
     };
 
-    Ok(Function {
+    Ok(Ok(Function {
         attributes,
         vis_marker,
         qualifiers,
@@ -220,5 +258,5 @@ pub(crate) fn consume_fn(
         return_ty,
         tk_semicolon,
         body: function_body,
-    })
+    }))
 }
@@ -1,11 +1,13 @@
+use crate::error::Error;
 use crate::parse_fn::{consume_fn, NotFunction};
 use crate::parse_mod::parse_mod;
 use crate::parse_type::{consume_bound, consume_generic_params, consume_where_clause};
 use crate::parse_utils::{
     consume_ident, consume_inner_attributes, consume_outer_attributes, consume_punct,
-    consume_stuff_until, consume_vis_marker, parse_any_ident, parse_ident, parse_punct,
+    consume_vis_marker, parse_any_ident, parse_ident, parse_punct, parse_stuff_until,
+    unexpected_token_error,
 };
-use crate::types::{Constant, ImplMember, TyDefinition, ValueExpr};
+use crate::types::{Constant, ExternBlock, ExternMember, ImplMember, TyDefinition, ValueExpr};
 use crate::types_edition::GroupSpan;
 use crate::{Attribute, Declaration, Impl, Trait, TraitMember, TyExpr, VisMarker};
 use proc_macro2::{Delimiter, Group, TokenTree};
@@ -17,37 +19,43 @@ pub(crate) fn parse_const_or_static(
     tokens: &mut TokenIter,
     attributes: Vec<Attribute>,
     vis_marker: Option<VisMarker>,
-) -> Constant {
+) -> Result<Constant, Error> {
     let (tk_const_or_static, is_const) = match tokens.next() {
         Some(TokenTree::Ident(ident)) if ident == "const" => (ident, true),
         Some(TokenTree::Ident(ident)) if ident == "static" => (ident, false),
-        _ => panic!("cannot parse const/static"),
-    };
-
-    let tk_mut = match tokens.peek() {
-        Some(TokenTree::Ident(ident)) if ident == "mut" => {
-            assert!(!is_const, "`const mut` is not a valid declaration");
-            Some(ident.clone())
+        token => {
+            return Err(unexpected_token_error(
+                token.as_ref(),
+                "const/static",
+                "`const` or `static`",
+            ))
         }
-        _ => None,
     };
 
-    let name = parse_any_ident(tokens, "const/static");
-    let tk_colon = parse_punct(tokens, ':', "const/static");
+    let tk_mut = consume_ident(tokens, "mut");
+    if let (true, Some(tk_mut)) = (is_const, &tk_mut) {
+        return Err(Error::new_at_span(
+            tk_mut.span(),
+            "cannot parse const: `const mut` is not a valid declaration",
+        ));
+    }
+
+    let name = parse_any_ident(tokens, "const/static")?;
+    let tk_colon = parse_punct(tokens, ':', "const/static")?;
 
-    let ty_tokens = consume_stuff_until(
+    let ty_tokens = parse_stuff_until(
         tokens,
         |tt| matches!(tt, TokenTree::Punct(punct) if punct.as_char() == '=' || punct.as_char() == ';'),
-        true,
-    );
+        "const/static",
+    )?;
 
     let tk_equals = consume_punct(tokens, '=');
 
-    let value_tokens = consume_stuff_until(
+    let value_tokens = parse_stuff_until(
         tokens,
         |tt| matches!(tt, TokenTree::Punct(punct) if punct.as_char() == ';'),
-        true,
-    );
+        "const/static",
+    )?;
     let initializer = if value_tokens.is_empty() {
         None
     } else {
@@ -56,9 +64,9 @@ pub(crate) fn parse_const_or_static(
         })
     };
 
-    let tk_semicolon = parse_punct(tokens, ';', "const/static");
+    let tk_semicolon = parse_punct(tokens, ';', "const/static")?;
 
-    Constant {
+    Ok(Constant {
         attributes,
         vis_marker,
         tk_const_or_static,
@@ -69,48 +77,57 @@ pub(crate) fn parse_const_or_static(
         tk_equals,
         initializer,
         tk_semicolon,
-    }
+    })
 }
 
 pub(crate) fn consume_ty_definition(
     tokens: &mut TokenIter,
     attributes: Vec<Attribute>,
     vis_marker: Option<VisMarker>,
-) -> Option<TyDefinition> {
+) -> Result<TyDefinition, Error> {
     let context = "associated type";
-    let tk_type = parse_ident(tokens, "type", context);
-    let name = parse_any_ident(tokens, context);
+    let tk_type = parse_ident(tokens, "type", context)?;
+    let name = parse_any_ident(tokens, context)?;
+    let generic_params = consume_generic_params(tokens)?;
 
-    let bound = consume_bound(tokens, |token| {
-        matches!(
-            token,
-            TokenTree::Punct(punct) if punct.as_char() == '=' || punct.as_char() == ';'
-        )
-    });
+    let bound = consume_bound(tokens, |token| match token {
+        TokenTree::Punct(punct) if punct.as_char() == '=' || punct.as_char() == ';' => true,
+        TokenTree::Ident(ident) if ident == "where" => true,
+        _ => false,
+    })?;
+    let where_clause = consume_where_clause(tokens)?;
 
     let tk_equals = consume_punct(tokens, '=');
 
     let initializer_ty = if tk_equals.is_some() {
-        let ty_tokens = consume_stuff_until(
+        let ty_tokens = parse_stuff_until(
             tokens,
-            |tt| matches!(tt, TokenTree::Punct(punct) if punct.as_char() == ';'),
-            true,
-        );
+            |tt| match tt {
+                TokenTree::Punct(punct) if punct.as_char() == ';' => true,
+                TokenTree::Ident(ident) if ident == "where" => true,
+                _ => false,
+            },
+            context,
+        )?;
         Some(TyExpr { tokens: ty_tokens })
     } else {
         None
     };
+    let trailing_where_clause = consume_where_clause(tokens)?;
 
-    let tk_semicolon = parse_punct(tokens, ';', context);
+    let tk_semicolon = parse_punct(tokens, ';', context)?;
 
-    Some(TyDefinition {
+    Ok(TyDefinition {
         attributes,
         vis_marker,
         tk_type,
         name,
+        generic_params,
         bound,
+        where_clause,
         tk_equals,
         initializer_ty,
+        trailing_where_clause,
         tk_semicolon,
     })
 }
@@ -119,98 +136,119 @@ pub(crate) fn consume_either_fn_type_const_static_impl(
     tokens: &mut TokenIter,
     attributes: Vec<Attribute>,
     vis_marker: Option<VisMarker>,
-    context: &str, // for panic
-) -> Declaration {
-    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
-        let keyword = ident.to_string();
-        match keyword.as_str() {
-            "type" => {
-                let assoc_ty = consume_ty_definition(tokens, attributes, vis_marker);
-                Declaration::TyDefinition(assoc_ty.unwrap())
-            }
-            "default" | "const" | "async" | "unsafe" | "extern" | "fn" => {
-                match consume_fn(tokens, attributes.clone(), vis_marker.clone()) {
-                    Ok(method) => Declaration::Function(method),
-                    Err(NotFunction::Const) => {
-                        let constant = parse_const_or_static(tokens, attributes, vis_marker);
-                        Declaration::Constant(constant)
-                    }
-                    Err(NotFunction::Trait) => {
-                        let trait_decl = parse_trait(tokens, attributes, vis_marker);
-                        Declaration::Trait(trait_decl)
-                    }
-                    Err(NotFunction::Impl) => {
-                        let impl_decl = parse_impl(tokens, attributes);
-                        Declaration::Impl(impl_decl)
-                    }
-                    Err(NotFunction::Mod) => {
-                        let mod_decl = parse_mod(tokens, attributes, vis_marker);
-                        Declaration::Module(mod_decl)
-                    }
+    context: &str, // for errors
+) -> Result<Declaration, Error> {
+    let declaration = match tokens.peek() {
+        Some(TokenTree::Ident(ident)) if ident == "type" => {
+            let assoc_ty = consume_ty_definition(tokens, attributes, vis_marker)?;
+            Declaration::TyDefinition(assoc_ty)
+        }
+        Some(TokenTree::Ident(ident))
+            if matches!(
+                ident.to_string().as_str(),
+                "default" | "const" | "async" | "unsafe" | "extern" | "fn"
+            ) =>
+        {
+            match consume_fn(tokens, attributes.clone(), vis_marker.clone())? {
+                Ok(method) => Declaration::Function(method),
+                Err(NotFunction::Const) => {
+                    let constant = parse_const_or_static(tokens, attributes, vis_marker)?;
+                    Declaration::Constant(constant)
+                }
+                Err(NotFunction::Trait) => {
+                    let trait_decl = parse_trait(tokens, attributes, vis_marker)?;
+                    Declaration::Trait(trait_decl)
+                }
+                Err(NotFunction::Impl) => {
+                    let impl_decl = parse_impl(tokens, attributes)?;
+                    Declaration::Impl(impl_decl)
+                }
+                Err(NotFunction::Mod) => {
+                    let mod_decl = parse_mod(tokens, attributes, vis_marker)?;
+                    Declaration::Module(mod_decl)
+                }
+                Err(NotFunction::ExternBlock) => {
+                    let extern_decl = parse_extern_block(tokens, attributes)?;
+                    Declaration::ExternBlock(extern_decl)
                 }
             }
-            _ => panic!("unsupported {} item `{}`", context, ident),
         }
-    } else {
-        panic!("unsupported {} element: {:?}", context, tokens.peek())
-    }
+        token => {
+            return Err(unexpected_token_error(
+                token,
+                context,
+                "one of `type`, `default`, `const`, `async`, `unsafe`, `extern` or `fn`",
+            ))
+        }
+    };
+    Ok(declaration)
 }
 
-pub(crate) fn parse_impl_body(token_group: Group) -> (GroupSpan, Vec<Attribute>, Vec<ImplMember>) {
+pub(crate) fn parse_impl_body(
+    token_group: Group,
+) -> Result<(GroupSpan, Vec<Attribute>, Vec<ImplMember>), Error> {
     let mut body_items = vec![];
 
     let mut tokens = token_group.stream().into_iter().peekable();
-    let inner_attributes = consume_inner_attributes(&mut tokens);
+    let inner_attributes = consume_inner_attributes(&mut tokens)?;
     loop {
         if tokens.peek().is_none() {
             break;
         }
 
-        let attributes = consume_outer_attributes(&mut tokens);
+        let attributes = consume_outer_attributes(&mut tokens)?;
         let vis_marker = consume_vis_marker(&mut tokens);
         let item = match consume_either_fn_type_const_static_impl(
             &mut tokens,
             attributes,
             vis_marker,
             "impl",
-        ) {
+        )? {
             Declaration::Function(function) => ImplMember::Method(function),
             Declaration::Constant(constant) => ImplMember::Constant(constant),
             Declaration::TyDefinition(ty_def) => ImplMember::AssocTy(ty_def),
-            _ => panic!("unsupported impl item `{:?}`", tokens.peek()),
+            declaration => {
+                return Err(Error::new_at_tokens(
+                    declaration,
+                    "cannot parse impl: unsupported impl item",
+                ))
+            }
         };
 
         body_items.push(item);
     }
 
-    (GroupSpan::new(&token_group), inner_attributes, body_items)
+    Ok((GroupSpan::new(&token_group), inner_attributes, body_items))
 }
 
-pub(crate) fn parse_impl(tokens: &mut TokenIter, attributes: Vec<Attribute>) -> Impl {
+pub(crate) fn parse_impl(
+    tokens: &mut TokenIter,
+    attributes: Vec<Attribute>,
+) -> Result<Impl, Error> {
     let tk_unsafe = consume_ident(tokens, "unsafe");
-    let tk_impl = parse_ident(tokens, "impl", "impl block");
+    let tk_impl = parse_ident(tokens, "impl", "impl block")?;
 
-    let impl_generic_params = consume_generic_params(tokens);
-    let trait_or_self_ty = consume_stuff_until(
+    let impl_generic_params = consume_generic_params(tokens)?;
+    let trait_or_self_ty = parse_stuff_until(
         tokens,
         |tk| match tk {
             TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => true,
             TokenTree::Ident(ident) if ident == "for" || ident == "where" => true,
             _ => false,
         },
-        true,
-    );
+        "impl block",
+    )?;
 
     let (tk_for, trait_ty, self_ty) = if let Some(tk_for) = consume_ident(tokens, "for") {
-        let self_ty = consume_stuff_until(
+        let self_ty = parse_stuff_until(
             tokens,
             |tk| match tk {
                 TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => true,
                 TokenTree::Ident(ident) if ident == "where" => true,
                 _ => false,
             },
-            true,
-        );
+            "impl block",
+        )?;
 
         (
             Some(tk_for),
@@ -229,14 +267,16 @@ pub(crate) fn parse_impl(tokens: &mut TokenIter, attributes: Vec<Attribute>) ->
         )
     };
 
-    let where_clause = consume_where_clause(tokens);
+    let where_clause = consume_where_clause(tokens)?;
 
-    let (tk_braces, inner_attributes, body_items) = match tokens.next().unwrap() {
-        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => parse_impl_body(group),
-        token => panic!("cannot parse impl: unexpected token {:?}", token),
+    let (tk_braces, inner_attributes, body_items) = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            parse_impl_body(group)?
+        }
+        token => return Err(unexpected_token_error(token.as_ref(), "impl block", "body")),
     };
 
-    Impl {
+    Ok(Impl {
         attributes,
         tk_unsafe,
         tk_impl,
@@ -248,29 +288,103 @@ pub(crate) fn parse_impl(tokens: &mut TokenIter, attributes: Vec<Attribute>) ->
         body_items,
         inner_attributes,
         tk_braces,
+    })
+}
+
+pub(crate) fn parse_extern_block(
+    tokens: &mut TokenIter,
+    attributes: Vec<Attribute>,
+) -> Result<ExternBlock, Error> {
+    let tk_unsafe = consume_ident(tokens, "unsafe");
+    let tk_extern = parse_ident(tokens, "extern", "extern block")?;
+    let extern_abi = match tokens.peek() {
+        Some(TokenTree::Literal(literal)) => {
+            let literal = literal.clone();
+            tokens.next();
+            Some(literal)
+        }
+        _ => None,
+    };
+
+    let group = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group,
+        token => {
+            return Err(unexpected_token_error(
+                token.as_ref(),
+                "extern block",
+                "body",
+            ))
+        }
+    };
+
+    let mut body_items = vec![];
+    let mut body_tokens = group.stream().into_iter().peekable();
+    let inner_attributes = consume_inner_attributes(&mut body_tokens)?;
+    loop {
+        if body_tokens.peek().is_none() {
+            break;
+        }
+
+        let attributes = consume_outer_attributes(&mut body_tokens)?;
+        let vis_marker = consume_vis_marker(&mut body_tokens);
+        let item = match body_tokens.peek() {
+            // `static` is not valid in impl bodies, so it needs to be handled here
+            Some(TokenTree::Ident(ident)) if ident == "static" => ExternMember::Static(
+                parse_const_or_static(&mut body_tokens, attributes, vis_marker)?,
+            ),
+            _ => match consume_either_fn_type_const_static_impl(
+                &mut body_tokens,
+                attributes,
+                vis_marker,
+                "extern block",
+            )? {
+                Declaration::Function(function) => ExternMember::Function(function),
+                Declaration::TyDefinition(ty_def) => ExternMember::Ty(ty_def),
+                declaration => {
+                    return Err(Error::new_at_tokens(
+                        declaration,
+                        "cannot parse extern block: unsupported extern block item",
+                    ))
+                }
+            },
+        };
+
+        body_items.push(item);
     }
+
+    Ok(ExternBlock {
+        attributes,
+        tk_unsafe,
+        tk_extern,
+        extern_abi,
+        tk_braces: GroupSpan::new(&group),
+        inner_attributes,
+        body_items,
+    })
 }
 
 pub(crate) fn parse_trait(
     tokens: &mut TokenIter,
     attributes: Vec<Attribute>,
     vis_marker: Option<VisMarker>,
-) -> Trait {
+) -> Result<Trait, Error> {
     let tk_unsafe = consume_ident(tokens, "unsafe");
-    let tk_trait = parse_ident(tokens, "trait", "trait declaration");
-    let name = parse_any_ident(tokens, "trait name");
-    let generic_params = consume_generic_params(tokens);
+    let tk_trait = parse_ident(tokens, "trait", "trait declaration")?;
+    let name = parse_any_ident(tokens, "trait name")?;
+    let generic_params = consume_generic_params(tokens)?;
     let bound = consume_bound(tokens, |token| match token {
         TokenTree::Ident(ident) if ident == "where" => true,
         TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => true,
         _ => false,
-    });
-    let where_clause = consume_where_clause(tokens);
+    })?;
+    let where_clause = consume_where_clause(tokens)?;
 
     // For trait body, at the moment reuse impl parsing
-    let (tk_braces, inner_attributes, body_items) = match tokens.next().unwrap() {
-        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => parse_impl_body(group),
-        token => panic!("cannot parse trait: unexpected token {:?}", token),
+    let (tk_braces, inner_attributes, body_items) = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            parse_impl_body(group)?
+        }
+        token => return Err(unexpected_token_error(token.as_ref(), "trait", "body")),
     };
 
     let body_items = body_items
@@ -282,7 +396,7 @@ pub(crate) fn parse_trait(
         })
         .collect();
 
-    Trait {
+    Ok(Trait {
         attributes,
         vis_marker,
         tk_unsafe,
@@ -294,5 +408,5 @@ pub(crate) fn parse_trait(
         body_items,
         inner_attributes,
         tk_braces,
-    }
+    })
 }
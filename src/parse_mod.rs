@@ -1,10 +1,11 @@
+use crate::error::Error;
 use crate::parse::parse_declaration_tokens;
 use crate::parse_type::consume_declaration_name;
 use crate::parse_utils::{
-    consume_ident, consume_inner_attributes, consume_stuff_until, parse_ident, parse_punct,
-    TokenIter,
+    consume_ident, consume_inner_attributes, consume_punct, parse_any_ident, parse_ident,
+    parse_punct, parse_stuff_until, unexpected_token_error, TokenIter,
 };
-use crate::{Attribute, GroupSpan, Module, TyExpr, UseDeclaration, VisMarker};
+use crate::{Attribute, GroupSpan, MacroDef, Module, TyExpr, UseDeclaration, VisMarker};
 use proc_macro2::token_stream::IntoIter;
 use proc_macro2::{Delimiter, TokenTree};
 use std::iter::Peekable;
@@ -13,20 +14,25 @@ pub(crate) fn parse_mod(
     tokens: &mut Peekable<IntoIter>,
     attributes: Vec<Attribute>,
     vis_marker: Option<VisMarker>,
-) -> Module {
-    // TODO some items currently unsupported: decl-macros, extern crate
+) -> Result<Module, Error> {
+    // TODO some items currently unsupported: extern crate
 
     let tk_unsafe = consume_ident(tokens, "unsafe");
-    let tk_mod = parse_ident(tokens, "mod", "module declaration");
-    let module_name = consume_declaration_name(tokens);
+    let tk_mod = parse_ident(tokens, "mod", "module declaration")?;
+    let module_name = consume_declaration_name(tokens)?;
 
-    let (group, tk_semicolon) = match tokens.next().unwrap() {
-        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => (Some(group), None),
-        TokenTree::Punct(punct) if punct.as_char() == ';' => (None, Some(punct)),
-        token => panic!(
-            "cannot parse mod: expected `{{ }}` or `;`, but got token {:?}",
-            token
-        ),
+    let (group, tk_semicolon) = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            (Some(group), None)
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => (None, Some(punct)),
+        token => {
+            return Err(unexpected_token_error(
+                token.as_ref(),
+                "mod",
+                "`{ }` or `;`",
+            ))
+        }
     };
 
     let inner_attributes;
@@ -38,13 +44,12 @@ pub(crate) fn parse_mod(
         let mut tokens = group.stream().into_iter().peekable();
 
         tk_braces = Some(GroupSpan::new(&group));
-        inner_attributes = consume_inner_attributes(&mut tokens);
+        inner_attributes = consume_inner_attributes(&mut tokens)?;
         loop {
             if tokens.peek().is_none() {
                 break;
             }
-            let item = parse_declaration_tokens(&mut tokens)
-                .unwrap_or_else(|e| panic!("declaration in mod: {}", e));
+            let item = parse_declaration_tokens(&mut tokens)?;
             mod_members.push(item);
         }
         members = mod_members;
@@ -54,7 +59,7 @@ pub(crate) fn parse_mod(
         members = vec![];
     }
 
-    Module {
+    Ok(Module {
         attributes,
         vis_marker,
         tk_unsafe,
@@ -64,28 +69,28 @@ pub(crate) fn parse_mod(
         tk_braces,
         inner_attributes,
         members,
-    }
+    })
 }
 
 pub(crate) fn parse_use_declaration(
     tokens: &mut TokenIter,
     attributes: Vec<Attribute>,
     vis_marker: Option<VisMarker>,
-) -> UseDeclaration {
-    let tk_use = parse_ident(tokens, "use", "use declaration");
+) -> Result<UseDeclaration, Error> {
+    let tk_use = parse_ident(tokens, "use", "use declaration")?;
 
-    let import_tree = consume_stuff_until(
+    let import_tree = parse_stuff_until(
         tokens,
         |token| match token {
             TokenTree::Punct(punct) if punct.as_char() == ';' => true,
             _ => false,
         },
-        true,
-    );
+        "use declaration",
+    )?;
 
-    let tk_semicolon = parse_punct(tokens, ';', "use declaration");
+    let tk_semicolon = parse_punct(tokens, ';', "use declaration")?;
 
-    UseDeclaration {
+    Ok(UseDeclaration {
         attributes,
         vis_marker,
         tk_use,
@@ -93,5 +98,42 @@ pub(crate) fn parse_use_declaration(
             tokens: import_tree,
         },
         tk_semicolon,
-    }
+    })
+}
+
+pub(crate) fn parse_macro_def(
+    tokens: &mut TokenIter,
+    attributes: Vec<Attribute>,
+) -> Result<MacroDef, Error> {
+    let tk_macro_rules = parse_ident(tokens, "macro_rules", "macro definition")?;
+    let tk_bang = parse_punct(tokens, '!', "macro definition")?;
+    let name = parse_any_ident(tokens, "macro definition")?;
+
+    let group = match tokens.next() {
+        Some(TokenTree::Group(group)) => group,
+        token => {
+            return Err(unexpected_token_error(
+                token.as_ref(),
+                "macro definition",
+                "rules group",
+            ))
+        }
+    };
+
+    // `macro_rules! name { ... }` needs no semicolon, the other forms do
+    let tk_semicolon = if group.delimiter() == Delimiter::Brace {
+        consume_punct(tokens, ';')
+    } else {
+        Some(parse_punct(tokens, ';', "macro definition")?)
+    };
+
+    Ok(MacroDef {
+        attributes,
+        tk_macro_rules,
+        tk_bang,
+        name,
+        tk_rules_group: GroupSpan::new(&group),
+        rules: group.stream(),
+        tk_semicolon,
+    })
 }
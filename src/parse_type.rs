@@ -1,13 +1,14 @@
 use crate::error::Error;
 use crate::parse_utils::{
     consume_colon2, consume_comma, consume_ident, consume_outer_attributes, consume_punct,
-    consume_stuff_until, consume_vis_marker, parse_any_ident, parse_punct,
+    consume_stuff_until, consume_stuff_until_into, consume_vis_marker, parse_any_ident,
+    parse_punct, parse_stuff_until, unexpected_token_error,
 };
 use crate::punctuated::Punctuated;
 use crate::types::{
-    EnumVariant, EnumVariantValue, GenericArg, GenericArgList, GenericBound, GenericParam,
-    GenericParamList, NamedField, NamedStructFields, StructFields, TupleField, TupleStructFields,
-    TyExpr, WhereClause, WhereClauseItem,
+    Attribute, EnumVariant, EnumVariantValue, GenericArg, GenericArgList, GenericBound,
+    GenericParam, GenericParamList, NamedField, NamedStructFields, StructFields, TupleField,
+    TupleStructFields, TyExpr, WhereClause, WhereClauseItem,
 };
 use crate::types_edition::GroupSpan;
 use proc_macro2::{Delimiter, Group, Ident, Punct, TokenStream, TokenTree};
@@ -16,70 +17,100 @@ use std::iter::Peekable;
 type TokenIter = Peekable<proc_macro2::token_stream::IntoIter>;
 
 // TODO - rename
-pub(crate) fn consume_declaration_name(tokens: &mut TokenIter) -> Ident {
+pub(crate) fn consume_declaration_name(tokens: &mut TokenIter) -> Result<Ident, Error> {
     parse_any_ident(tokens, "declaration")
 }
 
 pub(crate) fn consume_bound(
     tokens: &mut TokenIter,
     mut end_predicate: impl FnMut(&TokenTree) -> bool,
-) -> Option<GenericBound> {
-    match tokens.peek().unwrap() {
-        TokenTree::Punct(punct) if punct.as_char() == ':' => {
+) -> Result<Option<GenericBound>, Error> {
+    match tokens.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {
             let colon = punct.clone();
             // consume ':'
             tokens.next();
 
-            let bound_tokens = consume_stuff_until(tokens, end_predicate, false);
+            let bound_tokens = consume_stuff_until(tokens, end_predicate);
 
-            Some(GenericBound {
+            Ok(Some(GenericBound {
                 tk_colon: colon,
                 tokens: bound_tokens,
-            })
-        }
-        token if end_predicate(token) => None,
-        token => {
-            panic!("cannot parse generic bound: unexpected token {:?}", token)
+            }))
         }
+        Some(token) if end_predicate(token) => Ok(None),
+        // The caller reports the missing continuation
+        None => Ok(None),
+        Some(token) => Err(Error::new_at_span(
+            token.span(),
+            format!("cannot parse generic bound: unexpected token {:?}", token),
+        )),
     }
 }
 
-pub(crate) fn consume_generic_params(tokens: &mut TokenIter) -> Option<GenericParamList> {
+pub(crate) fn consume_generic_params(
+    tokens: &mut TokenIter,
+) -> Result<Option<GenericParamList>, Error> {
     let mut generic_params = Punctuated::new();
 
-    let gt = consume_punct(tokens, '<')?;
+    let gt = match consume_punct(tokens, '<') {
+        Some(gt) => gt,
+        None => return Ok(None),
+    };
 
     let lt: Punct;
     loop {
-        let token = tokens
-            .peek()
-            .expect("cannot parse generic params: expected token after '<'");
-        let prefix = match token {
-            TokenTree::Punct(punct) if punct.as_char() == '>' => {
+        let attributes = consume_outer_attributes(tokens)?;
+
+        let prefix = match tokens.peek() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => {
                 lt = punct.clone();
                 break;
             }
-            TokenTree::Punct(punct) if punct.as_char() == '\'' => Some(tokens.next().unwrap()),
-            TokenTree::Ident(ident) if ident == "const" => Some(tokens.next().unwrap()),
-            TokenTree::Ident(_ident) => None,
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '\'' => {
+                Some(tokens.next().unwrap())
+            }
+            Some(TokenTree::Ident(ident)) if ident == "const" => Some(tokens.next().unwrap()),
+            Some(TokenTree::Ident(_ident)) => None,
             token => {
-                panic!("cannot parse generic params: unexpected token {:?}", token)
+                return Err(unexpected_token_error(
+                    token,
+                    "generic params",
+                    "generic param or '>'",
+                ))
             }
         };
 
-        let name = parse_any_ident(tokens, "generic param name");
+        let name = parse_any_ident(tokens, "generic param name")?;
 
         let bound = consume_bound(
             tokens,
-            |token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ',' || punct.as_char() == '>'),
-        );
+            |token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ',' || punct.as_char() == '>' || punct.as_char() == '='),
+        )?;
+
+        let tk_equals = consume_punct(tokens, '=');
+        let default_value = match tk_equals {
+            Some(_) => {
+                let default_tokens = consume_stuff_until(
+                    tokens,
+                    |token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','),
+                );
+                Some(TyExpr {
+                    tokens: default_tokens,
+                })
+            }
+            None => None,
+        };
         let comma = consume_comma(tokens);
 
         generic_params.push(
             GenericParam {
+                attributes,
                 tk_prefix: prefix,
                 name,
                 bound,
+                tk_equals,
+                default_value,
             },
             comma,
         );
@@ -88,14 +119,14 @@ pub(crate) fn consume_generic_params(tokens: &mut TokenIter) -> Option<GenericPa
     // consume '>'
     tokens.next();
 
-    Some(GenericParamList {
+    Ok(Some(GenericParamList {
         tk_l_bracket: gt,
         params: generic_params,
         tk_r_bracket: lt,
-    })
+    }))
 }
 
-fn parse_generic_arg(tokens: Vec<TokenTree>) -> GenericArg {
+fn parse_generic_arg(tokens: Vec<TokenTree>) -> Result<GenericArg, Error> {
     // Note: method not called if tokens is empty
     let mut tokens = tokens.into_iter().peekable();
 
@@ -106,25 +137,21 @@ fn parse_generic_arg(tokens: Vec<TokenTree>) -> GenericArg {
             tokens.next(); // consume '
 
             // after the ', there must be a single identifier
-            match tokens.next() {
-                Some(TokenTree::Ident(ident)) => {
-                    assert!(
-                        tokens.next().is_none(),
-                        "cannot parse lifetime generic argument"
-                    );
-
-                    return GenericArg::Lifetime { tk_lifetime, ident };
+            return match (tokens.next(), tokens.next()) {
+                (Some(TokenTree::Ident(ident)), None) => {
+                    Ok(GenericArg::Lifetime { tk_lifetime, ident })
                 }
-                Some(other) => {
-                    panic!(
-                        "expected identifier after ' lifetime symbol, got {:?}",
-                        other
-                    );
-                }
-                None => {
-                    panic!("expected identifier after ' lifetime symbol, but ran out of tokens")
-                }
-            }
+                (Some(TokenTree::Ident(_)), token) => Err(unexpected_token_error(
+                    token.as_ref(),
+                    "lifetime generic argument",
+                    "',' or '>'",
+                )),
+                (token, _) => Err(unexpected_token_error(
+                    token.as_ref(),
+                    "lifetime generic argument",
+                    "identifier after ' lifetime symbol",
+                )),
+            };
         }
     }
 
@@ -137,11 +164,11 @@ fn parse_generic_arg(tokens: Vec<TokenTree>) -> GenericArg {
             if punct.as_char() == '=' {
                 let remaining: Vec<TokenTree> = tokens.collect();
 
-                return GenericArg::Binding {
+                return Ok(GenericArg::Binding {
                     ident,
                     tk_equals: punct,
                     ty: TyExpr { tokens: remaining },
-                };
+                });
             }
         }
     }
@@ -149,12 +176,14 @@ fn parse_generic_arg(tokens: Vec<TokenTree>) -> GenericArg {
     // Last, all the rest is just tokens
     let remaining: Vec<TokenTree> = before_ident.collect();
 
-    GenericArg::TyOrConst {
+    Ok(GenericArg::TyOrConst {
         expr: TyExpr { tokens: remaining },
-    }
+    })
 }
 
-pub(crate) fn consume_generic_args(tokens: &mut TokenIter) -> Option<GenericArgList> {
+pub(crate) fn consume_generic_args(
+    tokens: &mut TokenIter,
+) -> Result<Option<GenericArgList>, Error> {
     // TODO consider multiple-lookahead instead of potentially cloning many tokens
     let before_start = tokens.clone();
     let tk_turbofish_colons = consume_colon2(tokens);
@@ -167,7 +196,7 @@ pub(crate) fn consume_generic_args(tokens: &mut TokenIter) -> Option<GenericArgL
         }
         _ => {
             *tokens = before_start;
-            return None;
+            return Ok(None);
         }
     };
 
@@ -177,7 +206,6 @@ pub(crate) fn consume_generic_args(tokens: &mut TokenIter) -> Option<GenericArgL
         let arg_tokens = consume_stuff_until(
             tokens,
             |tk| matches!(tk, TokenTree::Punct(punct) if punct.as_char() == ','),
-            false,
         );
         let comma = consume_comma(tokens);
 
@@ -186,63 +214,54 @@ pub(crate) fn consume_generic_args(tokens: &mut TokenIter) -> Option<GenericArgL
             break;
         }
 
-        generic_args.push(parse_generic_arg(arg_tokens), comma);
+        generic_args.push(parse_generic_arg(arg_tokens)?, comma);
     }
 
-    let tk_r_bracket = parse_punct(tokens, '>', "end of generic argument list");
+    let tk_r_bracket = parse_punct(tokens, '>', "end of generic argument list")?;
 
-    Some(GenericArgList {
+    Ok(Some(GenericArgList {
         tk_turbofish_colons,
         tk_l_bracket,
         args: generic_args,
         tk_r_bracket,
-    })
+    }))
 }
 
-pub(crate) fn consume_where_clause(tokens: &mut TokenIter) -> Option<WhereClause> {
-    let where_token = consume_ident(tokens, "where")?;
+pub(crate) fn consume_where_clause(tokens: &mut TokenIter) -> Result<Option<WhereClause>, Error> {
+    let where_token = match consume_ident(tokens, "where") {
+        Some(where_token) => where_token,
+        None => return Ok(None),
+    };
 
     let mut items = Punctuated::new();
     loop {
-        let token = tokens
-            .peek()
-            .expect("cannot parse where clause: expected tokens");
-
-        match token {
-            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => break,
-            TokenTree::Punct(punct) if punct.as_char() == ';' => break,
-            _ => (),
-        };
-
-        let left_side = consume_stuff_until(
-            tokens,
-            |token| match token {
-                TokenTree::Punct(punct) if punct.as_char() == ':' => true,
-                _ => false,
-            },
-            false,
-        );
-
-        let colon = match tokens.next() {
-            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => punct.clone(),
-            Some(token) => panic!(
-                "cannot parse where clause: expected ':', found token {:?}",
-                token
-            ),
+        match tokens.peek() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => break,
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => break,
+            // End of the where clause in a type alias, eg `type A<T> where T: Clone = B<T>;`
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => break,
+            Some(_) => (),
             None => {
-                panic!("cannot parse where clause: expected colon, found end of stream")
+                return Err(Error::new(
+                    "cannot parse where clause: expected tokens, found end-of-stream",
+                ))
             }
         };
-        let bound_tokens = consume_stuff_until(
+
+        let left_side = consume_where_item_left_side(tokens);
+
+        let colon = parse_punct(tokens, ':', "where clause")?;
+        let bound_tokens = parse_stuff_until(
             tokens,
             |token| match token {
                 TokenTree::Punct(punct) if punct.as_char() == ',' => true,
                 TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => true,
                 TokenTree::Punct(punct) if punct.as_char() == ';' => true,
+                TokenTree::Punct(punct) if punct.as_char() == '=' => true,
                 _ => false,
             },
-            true,
-        );
+            "where clause",
+        )?;
 
         let comma = consume_comma(tokens);
 
@@ -258,29 +277,62 @@ pub(crate) fn consume_where_clause(tokens: &mut TokenIter) -> Option<WhereClause
         );
     }
 
-    Some(WhereClause {
+    Ok(Some(WhereClause {
         tk_where: where_token,
         items,
-    })
+    }))
+}
+
+/// Consumes the left side of a where-clause item, up to the bound's colon.
+///
+/// Path separators are part of the left side, eg `<T as Trait>::Assoc: Debug`.
+pub(crate) fn consume_where_item_left_side(tokens: &mut TokenIter) -> Vec<TokenTree> {
+    let mut left_side = Vec::new();
+    loop {
+        left_side.extend(consume_stuff_until(tokens, |token| match token {
+            TokenTree::Punct(punct) if punct.as_char() == ':' => true,
+            _ => false,
+        }));
+
+        match consume_colon2(tokens) {
+            Some([first, second]) => {
+                left_side.push(TokenTree::Punct(first));
+                left_side.push(TokenTree::Punct(second));
+            }
+            None => break,
+        }
+    }
+    left_side
+}
+
+pub(crate) fn consume_field_type(tokens: &mut TokenIter) -> Result<Vec<TokenTree>, Error> {
+    let mut field_type_tokens = Vec::new();
+    consume_field_type_into(tokens, &mut field_type_tokens)?;
+    Ok(field_type_tokens)
 }
 
-pub(crate) fn consume_field_type(tokens: &mut TokenIter) -> Vec<TokenTree> {
-    let field_type_tokens = consume_stuff_until(
+/// Same as [`consume_field_type`], but pushes the tokens to an empty `buffer`.
+///
+/// Field lists reuse one buffer, then copy each type out of it with its exact
+/// size, so long types don't reallocate as they grow. An enum shares a single
+/// buffer between all of its variants.
+fn consume_field_type_into(
+    tokens: &mut TokenIter,
+    buffer: &mut Vec<TokenTree>,
+) -> Result<(), Error> {
+    consume_stuff_until_into(
         tokens,
         |token| match token {
             TokenTree::Punct(punct) if punct.as_char() == ',' => true,
             _ => false,
         },
-        false,
+        buffer,
     );
 
-    if field_type_tokens.is_empty() && consume_comma(tokens).is_some() {
-        panic!("cannot parse type: unexpected token ','");
-    } else if field_type_tokens.is_empty() {
-        panic!("cannot parse type: expected tokens, found end-of-stream");
+    if buffer.is_empty() {
+        return Err(unexpected_token_error(tokens.peek(), "type", "tokens"));
     }
-
-    field_type_tokens
+    Ok(())
 }
 
 pub(crate) fn consume_enum_discriminant(
@@ -292,7 +344,15 @@ pub(crate) fn consume_enum_discriminant(
         return Ok(None);
     };
 
-    let value_token = tokens.next().unwrap();
+    let value_token = match tokens.next() {
+        Some(token) => token,
+        None => {
+            return Err(Error::new_at_span(
+                tk_equal.span(),
+                "cannot parse enum discriminant: expected value after '='",
+            ))
+        }
+    };
 
     // If the value expression has more than one token, we output an error.
     match tokens.peek() {
@@ -307,7 +367,10 @@ pub(crate) fn consume_enum_discriminant(
     }))
 }
 
-pub(crate) fn parse_tuple_fields(token_group: Group) -> TupleStructFields {
+pub(crate) fn parse_tuple_fields(
+    token_group: Group,
+    ty_buffer: &mut Vec<TokenTree>,
+) -> Result<TupleStructFields, Error> {
     let mut fields = Punctuated::new();
 
     let mut tokens = token_group.stream().into_iter().peekable();
@@ -316,10 +379,13 @@ pub(crate) fn parse_tuple_fields(token_group: Group) -> TupleStructFields {
             break;
         }
 
-        let attributes = consume_outer_attributes(&mut tokens);
+        let attributes = consume_outer_attributes(&mut tokens)?;
+        check_attributes_are_followed(&mut tokens, &attributes, "tuple fields")?;
         let vis_marker = consume_vis_marker(&mut tokens);
 
-        let ty_tokens = consume_field_type(&mut tokens);
+        consume_field_type_into(&mut tokens, ty_buffer)?;
+        let mut ty_tokens = Vec::with_capacity(ty_buffer.len());
+        ty_tokens.append(ty_buffer);
 
         let comma = consume_comma(&mut tokens);
 
@@ -333,28 +399,38 @@ pub(crate) fn parse_tuple_fields(token_group: Group) -> TupleStructFields {
         );
     }
 
-    TupleStructFields {
+    Ok(TupleStructFields {
         fields,
         tk_parens: GroupSpan::new(&token_group),
-    }
+    })
 }
 
-pub(crate) fn parse_named_fields(token_group: Group) -> NamedStructFields {
+pub(crate) fn parse_named_fields(
+    token_group: Group,
+    ty_buffer: &mut Vec<TokenTree>,
+) -> Result<NamedStructFields, Error> {
+    let mut tokens = token_group.stream().into_iter().peekable();
+
+    // Every field takes at least four top-level tokens (`a: T,`), which gives
+    // an upper bound on the number of fields without walking the body.
     let mut fields = Punctuated::new();
+    fields.inner.reserve((tokens.size_hint().0 + 1) / 4);
 
-    let mut tokens = token_group.stream().into_iter().peekable();
     loop {
         if tokens.peek().is_none() {
             break;
         }
 
-        let attributes = consume_outer_attributes(&mut tokens);
+        let attributes = consume_outer_attributes(&mut tokens)?;
+        check_attributes_are_followed(&mut tokens, &attributes, "named fields")?;
         let vis_marker = consume_vis_marker(&mut tokens);
 
-        let field_name = parse_any_ident(&mut tokens, "field name");
-        let colon = parse_punct(&mut tokens, ':', "named fields");
+        let field_name = parse_any_ident(&mut tokens, "field name")?;
+        let colon = parse_punct(&mut tokens, ':', "named fields")?;
 
-        let ty_tokens = consume_field_type(&mut tokens);
+        consume_field_type_into(&mut tokens, ty_buffer)?;
+        let mut ty_tokens = Vec::with_capacity(ty_buffer.len());
+        ty_tokens.append(ty_buffer);
         let comma = consume_comma(&mut tokens);
 
         fields.push(
@@ -369,14 +445,36 @@ pub(crate) fn parse_named_fields(token_group: Group) -> NamedStructFields {
         );
     }
 
-    NamedStructFields {
+    Ok(NamedStructFields {
         fields,
         tk_braces: GroupSpan::new(&token_group),
+    })
+}
+
+/// Returns an error if `attributes` are the last tokens of a list, eg a doc
+/// comment before the closing brace of a struct.
+///
+/// The compiler rejects these, but a token stream built by hand may have them.
+fn check_attributes_are_followed(
+    tokens: &mut TokenIter,
+    attributes: &[Attribute],
+    context: &str,
+) -> Result<(), Error> {
+    match (tokens.peek(), attributes.last()) {
+        (None, Some(attribute)) => Err(Error::new_at_tokens(
+            attribute,
+            format!(
+                "cannot parse {}: found an attribute with no item after it",
+                context
+            ),
+        )),
+        _ => Ok(()),
     }
 }
 
 pub(crate) fn parse_enum_variants(tokens: TokenStream) -> Result<Punctuated<EnumVariant>, Error> {
     let mut variants = Punctuated::new();
+    let mut ty_buffer = Vec::new();
 
     let mut tokens = tokens.into_iter().peekable();
     loop {
@@ -384,10 +482,11 @@ pub(crate) fn parse_enum_variants(tokens: TokenStream) -> Result<Punctuated<Enum
             break;
         }
 
-        let attributes = consume_outer_attributes(&mut tokens);
+        let attributes = consume_outer_attributes(&mut tokens)?;
+        check_attributes_are_followed(&mut tokens, &attributes, "enum variants")?;
         let vis_marker = consume_vis_marker(&mut tokens);
 
-        let variant_name = parse_any_ident(&mut tokens, "enum variant name");
+        let variant_name = parse_any_ident(&mut tokens, "enum variant name")?;
 
         let contents = match tokens.peek() {
             None => StructFields::Unit,
@@ -397,18 +496,23 @@ pub(crate) fn parse_enum_variants(tokens: TokenStream) -> Result<Punctuated<Enum
                 let group = group.clone();
                 // Consume group
                 tokens.next();
-                StructFields::Tuple(parse_tuple_fields(group))
+                StructFields::Tuple(parse_tuple_fields(group, &mut ty_buffer)?)
             }
             Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
                 let group = group.clone();
                 // Consume group
                 tokens.next();
-                StructFields::Named(parse_named_fields(group))
+                StructFields::Named(parse_named_fields(group, &mut ty_buffer)?)
+            }
+            Some(token) => {
+                return Err(Error::new_at_span(
+                    token.span(),
+                    format!("cannot parse enum variant: unexpected token {:?}", token),
+                ))
             }
-            token => panic!("cannot parse enum variant: unexpected token {:?}", token),
         };
 
-        let enum_discriminant = consume_enum_discriminant(&mut tokens);
+        let enum_discriminant = consume_enum_discriminant(&mut tokens)?;
 
         let comma = consume_comma(&mut tokens);
 
@@ -418,7 +522,7 @@ pub(crate) fn parse_enum_variants(tokens: TokenStream) -> Result<Punctuated<Enum
                 vis_marker,
                 name: variant_name,
                 contents,
-                value: enum_discriminant?,
+                value: enum_discriminant,
             },
             comma,
         );
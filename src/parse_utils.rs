@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::parse_type::consume_generic_args;
 use crate::types::{Attribute, AttributeValue, Path, PathSegment, VisMarker};
 use crate::types_edition::GroupSpan;
@@ -11,25 +12,52 @@ pub(crate) fn tokens_from_slice(slice: &[TokenTree]) -> TokenIter {
     stream.into_iter().peekable()
 }
 
-pub(crate) fn parse_any_ident(tokens: &mut TokenIter, panic_context: &str) -> Ident {
-    let next_token = tokens.next();
-    match next_token {
-        Some(TokenTree::Ident(ident)) => ident,
-        _ => panic!(
-            "cannot parse {}: expected identifier, got {:?}",
-            panic_context, next_token
+/// Returns an error for the unexpected `token` found while parsing `context`.
+///
+/// The error is located at the token, or at the call site if the stream ended.
+pub(crate) fn unexpected_token_error(
+    token: Option<&TokenTree>,
+    context: &str,
+    expected: &str,
+) -> Error {
+    match token {
+        Some(token) => Error::new_at_span(
+            token.span(),
+            format!(
+                "cannot parse {}: expected {}, found token {:?}",
+                context, expected, token
+            ),
         ),
+        None => Error::new(format!(
+            "cannot parse {}: expected {}, found end-of-stream",
+            context, expected
+        )),
     }
 }
 
-pub(crate) fn parse_ident(tokens: &mut TokenIter, expected: &str, panic_context: &str) -> Ident {
-    let next_token = tokens.next();
-    match next_token {
-        Some(TokenTree::Ident(ident)) if ident == expected => ident,
-        _ => panic!(
-            "cannot parse {}: expected `{}` ident, got {:?}",
-            panic_context, expected, next_token
-        ),
+pub(crate) fn parse_any_ident(tokens: &mut TokenIter, context: &str) -> Result<Ident, Error> {
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) => Ok(ident),
+        token => Err(unexpected_token_error(
+            token.as_ref(),
+            context,
+            "identifier",
+        )),
+    }
+}
+
+pub(crate) fn parse_ident(
+    tokens: &mut TokenIter,
+    expected: &str,
+    context: &str,
+) -> Result<Ident, Error> {
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident == expected => Ok(ident),
+        token => Err(unexpected_token_error(
+            token.as_ref(),
+            context,
+            &format!("`{}` ident", expected),
+        )),
     }
 }
 
@@ -44,14 +72,18 @@ pub(crate) fn consume_ident(tokens: &mut TokenIter, expected: &str) -> Option<Id
     }
 }
 
-pub(crate) fn parse_punct(tokens: &mut TokenIter, expected: char, panic_context: &str) -> Punct {
-    let next_token = tokens.next();
-    match next_token {
-        Some(TokenTree::Punct(punct)) if punct.as_char() == expected => punct,
-        _ => panic!(
-            "cannot parse {}: expected `{}` punct, got {:?}",
-            panic_context, expected, next_token
-        ),
+pub(crate) fn parse_punct(
+    tokens: &mut TokenIter,
+    expected: char,
+    context: &str,
+) -> Result<Punct, Error> {
+    match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == expected => Ok(punct),
+        token => Err(unexpected_token_error(
+            token.as_ref(),
+            context,
+            &format!("`{}` punct", expected),
+        )),
     }
 }
 
@@ -72,7 +104,10 @@ pub(crate) fn consume_punct(tokens: &mut TokenIter, expected: char) -> Option<Pu
 /// stop *before* any outer attributes `#[...]`.
 ///
 /// If `expect_inner` is false, only outer ones are consumed (encountering inner ones is an error).
-fn consume_attributes_with_inner(tokens: &mut TokenIter, expect_inner: bool) -> Vec<Attribute> {
+fn consume_attributes_with_inner(
+    tokens: &mut TokenIter,
+    expect_inner: bool,
+) -> Result<Vec<Attribute>, Error> {
     let mut attributes = Vec::new();
 
     loop {
@@ -98,7 +133,13 @@ fn consume_attributes_with_inner(tokens: &mut TokenIter, expect_inner: bool) ->
                     *tokens = before_attribute;
                     break;
                 }
-                _ => panic!("cannot parse inner attribute: expected '!' after '#' token"),
+                token => {
+                    return Err(unexpected_token_error(
+                        token,
+                        "inner attribute",
+                        "'!' after '#' token",
+                    ))
+                }
             }
         } else {
             tokens.next(); // consume '#'
@@ -107,12 +148,19 @@ fn consume_attributes_with_inner(tokens: &mut TokenIter, expect_inner: bool) ->
 
         let group = match tokens.next() {
             Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => group,
-            _ => {
-                if tk_bang.is_some() {
-                    panic!("cannot parse inner attribute: expected '[' after '#!' tokens")
-                } else {
-                    panic!("cannot parse outer attribute: expected '[' after '#' token")
-                }
+            token if tk_bang.is_some() => {
+                return Err(unexpected_token_error(
+                    token.as_ref(),
+                    "inner attribute",
+                    "'[' after '#!' tokens",
+                ))
+            }
+            token => {
+                return Err(unexpected_token_error(
+                    token.as_ref(),
+                    "outer attribute",
+                    "'[' after '#' token",
+                ))
             }
         };
 
@@ -127,7 +175,13 @@ fn consume_attributes_with_inner(tokens: &mut TokenIter, expect_inner: bool) ->
                 Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => break,
                 Some(TokenTree::Ident(_)) => (),
                 Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => (),
-                Some(token) => panic!("cannot parse attribute: expected one of `(`, `::`, `=`, `[`, `]`, or `{{`, found {:?}", token),
+                token => {
+                    return Err(unexpected_token_error(
+                        token,
+                        "attribute",
+                        "one of `(`, `::`, `=`, `[`, `]`, or `{`",
+                    ))
+                }
             };
             path.push(attribute_tokens.next().unwrap());
         }
@@ -142,7 +196,7 @@ fn consume_attributes_with_inner(tokens: &mut TokenIter, expect_inner: bool) ->
             Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
                 let tk_equals = punct.clone();
                 attribute_tokens.next();
-                let value = attribute_tokens.into_iter().collect();
+                let value = attribute_tokens.collect();
                 AttributeValue::Equals(tk_equals, value)
             }
             _ => unreachable!(),
@@ -157,29 +211,27 @@ fn consume_attributes_with_inner(tokens: &mut TokenIter, expect_inner: bool) ->
         });
     }
 
-    attributes
+    Ok(attributes)
 }
 
 /// Outer macro attributes of the form `#[attribute]`
 ///
-/// Panics if any inner attributes such as `#![attribute]` are encountered.
-pub(crate) fn consume_outer_attributes(tokens: &mut TokenIter) -> Vec<Attribute> {
+/// Returns an error if any inner attributes such as `#![attribute]` are encountered.
+pub(crate) fn consume_outer_attributes(tokens: &mut TokenIter) -> Result<Vec<Attribute>, Error> {
     consume_attributes_with_inner(tokens, false)
 }
 
 /// Inner macro attributes of the form `#![attribute]`.
 ///
 /// Stops _before_ encountering any outer attributes such as `#[attribute]`.
-pub(crate) fn consume_inner_attributes(tokens: &mut TokenIter) -> Vec<Attribute> {
+pub(crate) fn consume_inner_attributes(tokens: &mut TokenIter) -> Result<Vec<Attribute>, Error> {
     consume_attributes_with_inner(tokens, true)
 }
 
 pub(crate) fn consume_vis_marker(tokens: &mut TokenIter) -> Option<VisMarker> {
     match tokens.peek() {
         Some(TokenTree::Ident(ident)) if ident == "pub" => {
-            let pub_token = tokens
-                .next()
-                .expect("cannot parse declaration: expected token after 'pub'");
+            let pub_token = tokens.next().unwrap();
             match tokens.peek() {
                 Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
                     Some(VisMarker {
@@ -208,12 +260,45 @@ pub(crate) fn consume_vis_marker(tokens: &mut TokenIter) -> Option<VisMarker> {
 pub(crate) fn consume_stuff_until(
     tokens: &mut TokenIter,
     predicate: impl FnMut(&TokenTree) -> bool,
-    must_find_predicate: bool,
 ) -> Vec<TokenTree> {
     let mut output_tokens = Vec::new();
+    consume_stuff_until_into(tokens, predicate, &mut output_tokens);
+    output_tokens
+}
+
+/// Same as [`consume_stuff_until`], but returns an error unless a separator
+/// matching `predicate` is found.
+pub(crate) fn parse_stuff_until(
+    tokens: &mut TokenIter,
+    predicate: impl FnMut(&TokenTree) -> bool,
+    context: &str,
+) -> Result<Vec<TokenTree>, Error> {
+    let output_tokens = consume_stuff_until(tokens, predicate);
+
+    // `consume_stuff_until` only stops early at a separator or an unbalanced `>`
+    match tokens.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => Err(Error::new_at_span(
+            punct.span(),
+            format!("cannot parse {}: unbalanced angle brackets", context),
+        )),
+        Some(_) => Ok(output_tokens),
+        None => Err(Error::new(format!(
+            "cannot parse {}: unexpected end of token stream",
+            context
+        ))),
+    }
+}
+
+/// Same as [`consume_stuff_until`], but pushes the tokens to `output_tokens`.
+///
+/// Lets hot loops reuse a single buffer instead of growing a new `Vec` each time.
+pub(crate) fn consume_stuff_until_into(
+    tokens: &mut TokenIter,
+    predicate: impl FnMut(&TokenTree) -> bool,
+    output_tokens: &mut Vec<TokenTree>,
+) {
     let mut bracket_count = 0;
     let mut predicate = predicate;
-    let mut predicate_met = false;
     let mut prev_token_is_dash = false;
 
     loop {
@@ -228,8 +313,9 @@ pub(crate) fn consume_stuff_until(
                 false
             }
             Some(TokenTree::Punct(punct)) if punct.as_char() == '-' => true,
+            // The `>` of a `->` arrow is never a separator
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => false,
             Some(token) if predicate(token) && bracket_count == 0 => {
-                predicate_met = true;
                 break;
             }
             None => {
@@ -246,20 +332,6 @@ pub(crate) fn consume_stuff_until(
 
         output_tokens.push(tokens.next().unwrap());
     }
-
-    if must_find_predicate && !predicate_met {
-        match tokens.next() {
-            Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => {
-                panic!("unbalanced angle brackets in type expression");
-            }
-            None => {
-                panic!("unexpected end of token stream in type expression");
-            }
-            _ => unreachable!(),
-        }
-    }
-
-    output_tokens
 }
 
 pub(crate) fn consume_comma(tokens: &mut TokenIter) -> Option<Punct> {
@@ -301,7 +373,7 @@ pub(crate) fn consume_path(mut tokens: TokenIter) -> Option<Path> {
             _ => return None, // end of tokens OR not a path
         };
 
-        let generic_args = consume_generic_args(&mut tokens);
+        let generic_args = consume_generic_args(&mut tokens).ok()?;
 
         segments.push(PathSegment {
             tk_separator_colons,
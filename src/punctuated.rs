@@ -1,5 +1,6 @@
 use proc_macro2::{Punct, Spacing, TokenStream};
 use quote::{ToTokens, TokenStreamExt as _};
+use std::hash::{Hash, Hasher};
 
 // Inspired by syn's `Punctuated` type.
 
@@ -49,11 +50,56 @@ impl<T> Punctuated<T> {
         }
     }
 
+    /// Removes and returns the element at position `index`.
+    ///
+    /// If the removed element was the last one, the new last element keeps
+    /// the trailing comma setting of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len());
+
+        let (value, _punct) = self.inner.remove(index);
+        if self.inner.is_empty() {
+            self.skip_last = false;
+        }
+        value
+    }
+
     /// Return an interator that reads items.
     pub fn items(&self) -> impl Iterator<Item = &T> {
         self.inner.iter().map(|(item, _punct)| item)
     }
 
+    /// Return an interator that mutates items.
+    pub fn items_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.inner.iter_mut().map(|(item, _punct)| item)
+    }
+
+    /// Return an interator that reads items, same as [`Punctuated::items`].
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items()
+    }
+
+    /// Return an interator that mutates items, same as [`Punctuated::items_mut`].
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.items_mut()
+    }
+
+    /// Return the item at `index`, or None if out of bounds.
+    ///
+    /// Note that indexing with `list[index]` returns the `(item, comma)` pair instead.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.inner.get(index).map(|(item, _punct)| item)
+    }
+
+    /// Return the item at `index` mutably, or None if out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.inner.get_mut(index).map(|(item, _punct)| item)
+    }
+
     /// Return an interator that reads commas.
     pub fn punct(&self) -> impl Iterator<Item = &Punct> {
         let len = self.inner.len();
@@ -65,6 +111,23 @@ impl<T> Punctuated<T> {
         slice.iter().map(|(_item, punct)| punct)
     }
 
+    /// Return true if the last item is followed by a comma.
+    ///
+    /// Always false for an empty list. The trailing comma is preserved when
+    /// converting the list back to tokens.
+    pub fn has_trailing_punct(&self) -> bool {
+        !self.inner.is_empty() && !self.skip_last
+    }
+
+    /// Set whether the last item is followed by a comma.
+    ///
+    /// Has no effect on an empty list.
+    pub fn set_trailing_punct(&mut self, trailing_punct: bool) {
+        if !self.inner.is_empty() {
+            self.skip_last = !trailing_punct;
+        }
+    }
+
     /// Return number of items.
     pub fn len(&self) -> usize {
         self.inner.len()
@@ -84,6 +147,15 @@ impl<T> Default for Punctuated<T> {
     }
 }
 
+impl<'a, T> IntoIterator for &'a Punctuated<T> {
+    type Item = &'a T;
+    type IntoIter = std::iter::Map<std::slice::Iter<'a, (T, Punct)>, fn(&'a (T, Punct)) -> &'a T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter().map(|(item, _punct)| item)
+    }
+}
+
 impl<T: std::fmt::Debug> std::fmt::Debug for Punctuated<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut list = f.debug_list();
@@ -94,6 +166,26 @@ impl<T: std::fmt::Debug> std::fmt::Debug for Punctuated<T> {
     }
 }
 
+/// Compares items and trailing comma, ignoring the spans of commas.
+impl<T: PartialEq> PartialEq for Punctuated<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items().eq(other.items()) && self.has_trailing_punct() == other.has_trailing_punct()
+    }
+}
+
+impl<T: Eq> Eq for Punctuated<T> {}
+
+/// Hashes items and trailing comma, ignoring the spans of commas.
+impl<T: Hash> Hash for Punctuated<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.len().hash(state);
+        for item in self.items() {
+            item.hash(state);
+        }
+        self.has_trailing_punct().hash(state);
+    }
+}
+
 impl<T: ToTokens> ToTokens for Punctuated<T> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         if self.inner.is_empty() {
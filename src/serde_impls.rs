@@ -0,0 +1,105 @@
+// Declarations are (de)serialized as the string of their tokens. Spans are
+// lost across the round trip: deserialized tokens are located at the call site.
+
+use crate::{
+    parse_declaration, Constant, Declaration, Enum, ExternBlock, Function, Impl, MacroDef, Module,
+    Struct, Trait, TyDefinition, TyExpr, Union, UseDeclaration, ValueExpr,
+};
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+fn serialize_tokens<S: Serializer>(
+    value: &impl ToTokens,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_token_stream().to_string())
+}
+
+fn deserialize_tokens<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TokenStream, D::Error> {
+    let source = String::deserialize(deserializer)?;
+    source.parse().map_err(D::Error::custom)
+}
+
+fn deserialize_declaration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Declaration, D::Error> {
+    let tokens = deserialize_tokens(deserializer)?;
+    parse_declaration(tokens).map_err(D::Error::custom)
+}
+
+impl Serialize for Declaration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_tokens(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Declaration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_declaration(deserializer)
+    }
+}
+
+macro_rules! implement_declaration_serde {
+    ($($Kind:ident => $Variant:ident),* $(,)?) => {
+        $(
+            impl Serialize for $Kind {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serialize_tokens(self, serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $Kind {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    match deserialize_declaration(deserializer)? {
+                        Declaration::$Variant(declaration) => Ok(declaration),
+                        _ => Err(D::Error::custom(concat!(
+                            "expected ",
+                            stringify!($Kind),
+                            " declaration"
+                        ))),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+implement_declaration_serde! {
+    Struct => Struct,
+    Enum => Enum,
+    Union => Union,
+    Module => Module,
+    Trait => Trait,
+    Impl => Impl,
+    ExternBlock => ExternBlock,
+    TyDefinition => TyDefinition,
+    Function => Function,
+    Constant => Constant,
+    UseDeclaration => Use,
+    MacroDef => MacroDef,
+}
+
+macro_rules! implement_expr_serde {
+    ($($Kind:ident),* $(,)?) => {
+        $(
+            impl Serialize for $Kind {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serialize_tokens(self, serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $Kind {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let tokens = deserialize_tokens(deserializer)?;
+                    Ok($Kind {
+                        tokens: tokens.into_iter().collect(),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+implement_expr_serde! { TyExpr, ValueExpr }
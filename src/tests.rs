@@ -1,10 +1,15 @@
-use crate::{parse_declaration, Declaration, GenericParam, Struct, TyExpr, WhereClauseItem};
+use crate::{
+    assert_tokens_eq, parse_declaration, parse_declaration_str, parse_declarations, tokens_equal,
+    Attribute, DeclKind, Declaration, Enum, EnumVariant, ExternMember, FnParam, GenericParam,
+    GenericParamKind, GenericParamList, HasGenerics, ImplMember, NamedField, Struct, StructFields,
+    TraitMember, TupleField, TyExpr, VisMarker, WhereClauseItem,
+};
 
 use crate::parse_type::consume_generic_args;
 use crate::types::GenericArgList;
 use insta::assert_debug_snapshot;
-use proc_macro2::TokenStream;
-use quote::quote;
+use proc_macro2::{Delimiter, TokenStream};
+use quote::{quote, ToTokens};
 
 // TODO - check test coverage
 
@@ -29,7 +34,7 @@ fn parse_generic_args_checked(tokens: TokenStream) -> GenericArgList {
     let initial_tokens = tokens.clone();
 
     let mut token_iter = tokens.into_iter().peekable();
-    let generic_args = consume_generic_args(&mut token_iter).unwrap();
+    let generic_args = consume_generic_args(&mut token_iter).unwrap().unwrap();
 
     similar_asserts::assert_str_eq!(quote!(#generic_args), initial_tokens);
 
@@ -122,6 +127,143 @@ fn parse_empty_enum() {
     assert_debug_snapshot!(enum_type);
 }
 
+#[test]
+fn parse_raw_idents() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct r#struct<r#T> {
+            r#type: r#T,
+            normal: u8,
+        }
+    ));
+    let struct_type = struct_type.as_struct().unwrap();
+
+    assert_eq!(struct_type.name.to_string(), "r#struct");
+    let field_names: Vec<_> = struct_type.field_names().collect();
+    assert_eq!(field_names, ["r#type", "normal"]);
+
+    let built_struct = Struct::new("r#struct")
+        .with_param(GenericParam::ty("r#T"))
+        .with_named_field(NamedField::new("r#type", TyExpr::from_tokens(quote!(r#T))));
+    assert_eq!(
+        built_struct.to_token_stream().to_string(),
+        "struct r#struct < r#T > { r#type : r#T }"
+    );
+}
+
+#[test]
+fn compare_tokens() {
+    let tokens = quote!(
+        fn hello(a: &'a [u8; 4]) -> Option<Vec<u8>> {
+            a >> 2
+        }
+    );
+    let same_tokens: TokenStream = "fn hello(a: &'a [u8; 4]) -> Option<Vec<u8>> { a >> 2 }"
+        .parse()
+        .unwrap();
+
+    assert!(tokens_equal(&tokens, &same_tokens));
+    assert!(!tokens_equal(&quote!(a > > 2), &quote!(a >= 2)));
+    assert!(!tokens_equal(&quote!((a)), &quote!([a])));
+    assert!(!tokens_equal(&quote!(a b), &quote!(a)));
+    assert!(!tokens_equal(&quote!(1), &quote!(1u8)));
+    assert_tokens_eq(tokens, same_tokens);
+}
+
+#[test]
+#[should_panic(expected = "token streams are not equal")]
+fn compare_tokens_mismatch() {
+    assert_tokens_eq(
+        quote!(
+            struct A;
+        ),
+        quote!(
+            struct B;
+        ),
+    );
+}
+
+#[test]
+fn compare_declarations() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<A> {
+            a: A,
+        }
+    ));
+    let same_struct_type = parse_declaration_checked(
+        "struct Hello<A> { a: A, }"
+            .parse::<TokenStream>()
+            .unwrap()
+            .into_iter()
+            .map(|mut token| {
+                token.set_span(proc_macro2::Span::mixed_site());
+                token
+            })
+            .collect(),
+    );
+    let other_struct_type = parse_declaration_checked(quote!(
+        struct Hello<A> {
+            b: A,
+        }
+    ));
+    let edited_struct_type = parse_struct_declaration(quote!(
+        struct Hello {
+            a: A,
+        }
+    ))
+    .with_param(GenericParam::ty("A"));
+
+    assert_eq!(struct_type, same_struct_type);
+    assert_ne!(struct_type, other_struct_type);
+    assert_eq!(struct_type, Declaration::Struct(edited_struct_type));
+    assert_eq!(
+        struct_type.generic_params(),
+        other_struct_type.generic_params()
+    );
+}
+
+#[test]
+fn hash_field_types() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello {
+            a: Vec<u8>,
+            b: Option<i32>,
+            c: Vec<u8>,
+            d: std::vec::Vec<u8>,
+        }
+    ));
+
+    let fields = match &struct_type.fields {
+        StructFields::Named(fields) => fields,
+        _ => unreachable!(),
+    };
+    let unique_types: std::collections::HashSet<_> =
+        fields.fields.items().map(|field| &field.ty).collect();
+
+    assert_eq!(unique_types.len(), 3);
+}
+
+#[test]
+fn parse_from_str() {
+    let declaration = parse_declaration_str(
+        "
+        #[derive(Debug)]
+        pub struct Hello<T> {
+            a: Vec<T>,
+        }
+        ",
+    )
+    .unwrap();
+    let expected = parse_declaration(quote!(
+        #[derive(Debug)]
+        pub struct Hello<T> {
+            a: Vec<T>,
+        }
+    ))
+    .unwrap();
+
+    assert_tokens_eq(declaration.to_token_stream(), expected.to_token_stream());
+}
+
 // ==========
 // VISIBILITY
 // ==========
@@ -238,6 +380,103 @@ fn parse_tuple_fields_vis() {
     assert_debug_snapshot!(struct_type);
 }
 
+#[test]
+fn interpret_tuple_fields_vis() {
+    let struct_type = parse_declaration_checked(quote!(
+        pub struct Hello(pub u32, pub(in crate::foo) A, String);
+    ));
+
+    let fields = match &struct_type.as_struct().unwrap().fields {
+        StructFields::Tuple(fields) => fields,
+        _ => unreachable!(),
+    };
+    let vis_markers: Vec<_> = fields
+        .fields
+        .items()
+        .map(|field| {
+            field
+                .vis_marker
+                .as_ref()
+                .map(|vis_marker| vis_marker.to_token_stream().to_string())
+        })
+        .collect();
+
+    assert_eq!(
+        vis_markers,
+        [
+            Some("pub".to_string()),
+            Some("pub (in crate :: foo)".to_string()),
+            None
+        ]
+    );
+}
+
+#[test]
+fn interpret_vis_markers() {
+    let vis_markers: Vec<_> = [
+        quote!(
+            pub struct Hello;
+        ),
+        quote!(
+            pub(crate) struct Hello;
+        ),
+        quote!(crate struct Hello;),
+        quote!(
+            pub(super) struct Hello;
+        ),
+        quote!(
+            pub(in crate::foo) struct Hello;
+        ),
+    ]
+    .into_iter()
+    .map(|tokens| {
+        let struct_decl = parse_declaration_checked(tokens);
+        struct_decl.as_struct().unwrap().vis_marker.clone().unwrap()
+    })
+    .collect();
+
+    let is_pub: Vec<_> = vis_markers.iter().map(|vis| vis.is_pub()).collect();
+    let is_crate: Vec<_> = vis_markers.iter().map(|vis| vis.is_crate()).collect();
+    let restricted_paths: Vec<_> = vis_markers
+        .iter()
+        .map(|vis| vis.restricted_path().map(|path| path.to_string()))
+        .collect();
+
+    assert_eq!(is_pub, [true, false, false, false, false]);
+    assert_eq!(is_crate, [false, true, true, false, false]);
+    assert_eq!(
+        restricted_paths,
+        [None, None, None, None, Some("crate :: foo".to_string())]
+    );
+    assert!(VisMarker::pub_vis().is_pub());
+}
+
+#[test]
+fn parse_restricted_field_vis() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello {
+            pub(in crate::foo::bar) a: A,
+            pub(in super::baz) b: B,
+        }
+    ));
+    let tuple_type = parse_declaration_checked(quote!(
+        struct Hello(pub(in self::foo) A);
+    ));
+
+    let restricted_paths: Vec<_> = struct_type
+        .as_struct()
+        .unwrap()
+        .fields_iter()
+        .chain(tuple_type.as_struct().unwrap().fields_iter())
+        .map(|field| field.vis().unwrap().restricted_path().unwrap().to_string())
+        .collect();
+
+    assert_eq!(
+        restricted_paths,
+        ["crate :: foo :: bar", "super :: baz", "self :: foo"]
+    );
+}
+
 // ==========
 // ATTRIBUTES
 // ==========
@@ -341,6 +580,269 @@ fn parse_complex_attributes() {
     assert_debug_snapshot!(struct_type.attributes()[1].get_single_path_segment());
 }
 
+#[test]
+fn interpret_attribute_path() {
+    let struct_type = parse_declaration_checked(quote!(
+        #[serde::skip]
+        #[hello(a b c)]
+        struct Hello;
+    ));
+
+    let attributes = struct_type.attributes();
+    let path: Vec<_> = attributes[0]
+        .get_path_segments()
+        .map(|ident| ident.to_string())
+        .collect();
+    assert_eq!(path, ["serde", "skip"]);
+    assert!(!attributes[0].is_inner());
+
+    assert_eq!(attributes[1].get_path_segments().count(), 1);
+    assert_quote_snapshot!(TokenStream::from_iter(
+        attributes[1].get_value_tokens().iter().cloned()
+    ));
+}
+
+#[test]
+fn interpret_cfg_attributes() {
+    let enum_type = parse_declaration_checked(quote!(
+        #[cfg_attr(test, derive(Debug))]
+        enum Hello {
+            #[cfg(feature = "a")]
+            A,
+            B {
+                #[cfg(not(windows))]
+                b: B,
+                #[some::cfg]
+                c: C,
+            },
+        }
+    ));
+    let enum_type = enum_type.as_enum().unwrap();
+
+    assert!(!enum_type.attributes[0].is_cfg());
+    assert!(enum_type.attributes[0].cfg_predicate().is_none());
+
+    let variant_a = &enum_type.variants[0].0;
+    assert!(variant_a.attributes[0].is_cfg());
+    assert_eq!(
+        variant_a.attributes[0].cfg_predicate().unwrap().to_string(),
+        "feature = \"a\""
+    );
+
+    let fields: Vec<_> = enum_type.variants[1]
+        .0
+        .fields_iter()
+        .map(|field| {
+            field.attributes()[0]
+                .cfg_predicate()
+                .map(|predicate| predicate.to_string())
+        })
+        .collect();
+    assert_eq!(fields, [Some("not (windows)".to_string()), None]);
+}
+
+#[test]
+fn interpret_derives() {
+    let struct_type = parse_declaration_checked(quote!(
+        #[derive(Clone, Copy)]
+        #[serde(rename_all = "camelCase")]
+        #[derive(::serde::Serialize, PartialEq)]
+        struct Hello;
+    ));
+    let unit_struct = parse_declaration_checked(quote!(
+        #[rustfmt::skip]
+        struct Hello;
+    ));
+
+    let derives: Vec<_> = struct_type
+        .derives()
+        .iter()
+        .map(|ident| ident.to_string())
+        .collect();
+    assert_eq!(derives, ["Clone", "Copy", "Serialize", "PartialEq"]);
+
+    let derive_paths: Vec<_> = struct_type
+        .as_struct()
+        .unwrap()
+        .derive_paths()
+        .iter()
+        .map(|path| path.to_string())
+        .collect();
+    assert_eq!(
+        derive_paths,
+        ["Clone", "Copy", ":: serde :: Serialize", "PartialEq"]
+    );
+
+    assert!(unit_struct.derives().is_empty());
+}
+
+#[test]
+fn interpret_attribute_lookup() {
+    let struct_type = parse_declaration_checked(quote!(
+        #[serde::skip]
+        #[hello(a b c)]
+        #[hello(d e f)]
+        struct Hello(#[serde(rename = "b")] A, B);
+    ));
+
+    assert!(struct_type.has_attribute("skip"));
+    assert!(!struct_type.has_attribute("serde"));
+    assert_quote_snapshot!(struct_type.get_attribute("hello").unwrap());
+
+    let fields = match &struct_type.as_struct().unwrap().fields {
+        StructFields::Tuple(fields) => fields,
+        _ => unreachable!(),
+    };
+    assert!(fields.fields[0].0.has_attribute("serde"));
+    assert!(fields.fields[1].0.get_attribute("serde").is_none());
+}
+
+#[test]
+fn edit_remove_attribute() {
+    let mut struct_type = parse_declaration_checked(quote!(
+        #[my_macro::helper]
+        #[derive(Clone)]
+        #[helper(second)]
+        struct Hello {
+            #[helper]
+            a: A,
+        }
+    ));
+
+    let removed = struct_type.remove_attribute("helper").unwrap();
+    assert_eq!(removed.get_path_segments().count(), 2);
+    assert!(struct_type.remove_attribute("serde").is_none());
+
+    let names: Vec<_> = struct_type
+        .attributes()
+        .iter()
+        .map(|attribute| attribute.get_path_segments().last().unwrap().to_string())
+        .collect();
+    assert_eq!(names, ["derive", "helper"]);
+
+    let field = match &mut struct_type {
+        Declaration::Struct(Struct {
+            fields: StructFields::Named(fields),
+            ..
+        }) => &mut fields.fields[0].0,
+        _ => unreachable!(),
+    };
+    assert!(field.remove_attribute("helper").is_some());
+    assert!(field.attributes().is_empty());
+}
+
+#[test]
+fn compare_ignoring_attributes() {
+    let struct_a = parse_struct_declaration(quote!(
+        /// Doc
+        #[derive(Clone)]
+        struct Hello<T> {
+            /// Field doc
+            a: T,
+        }
+    ));
+    let struct_b = parse_struct_declaration(quote!(
+        struct Hello<T> {
+            #[serde(skip)]
+            a: T,
+        }
+    ));
+    let struct_c = parse_struct_declaration(quote!(
+        struct Hello<T> {
+            b: T,
+        }
+    ));
+
+    assert_ne!(struct_a, struct_b);
+    assert!(struct_a.eq_ignoring_attrs(&struct_b));
+    assert!(!struct_a.eq_ignoring_attrs(&struct_c));
+
+    let enum_a = parse_declaration_checked(quote!(
+        #[repr(u8)]
+        enum Hello {
+            /// Variant doc
+            A(#[attr] u8),
+            B,
+        }
+    ));
+    let enum_b = parse_declaration_checked(quote!(
+        enum Hello {
+            A(u8),
+            B,
+        }
+    ));
+    let enum_a = enum_a.as_enum().unwrap();
+    let enum_b = enum_b.as_enum().unwrap();
+
+    assert!(enum_a.eq_ignoring_attrs(enum_b));
+    assert!(!enum_a.eq_ignoring_attrs(&enum_b.clone().with_variant(EnumVariant::unit("C"))));
+}
+
+#[test]
+fn interpret_enum_variant_attributes() {
+    let enum_type = parse_declaration_checked(quote!(
+        enum Hello {
+            #[serde(rename = "a")]
+            A,
+            B(#[serde(skip)] Foo),
+            #[doc = "Variant C"]
+            #[serde(rename = "c")]
+            C {
+                foo: Foo,
+            },
+        }
+    ));
+    let variants = &enum_type.as_enum().unwrap().variants;
+
+    assert_quote_snapshot!(variants[0].0.get_attribute("serde").unwrap());
+    assert!(!variants[1].0.has_attribute("serde"));
+    assert_eq!(variants[2].0.attributes().len(), 2);
+    assert_quote_snapshot!(variants[2].0.get_attribute("serde").unwrap());
+}
+
+#[test]
+fn interpret_repr_attributes() {
+    let enum_type = parse_declaration_checked(quote!(
+        #[repr(C, u8)]
+        #[repr(align(8), packed, packed(2), simd)]
+        enum Hello {
+            A,
+        }
+    ));
+    let struct_type = parse_declaration_checked(quote!(
+        #[derive(Clone)]
+        struct Hello;
+    ));
+
+    assert_debug_snapshot!(enum_type.as_enum().unwrap().repr());
+    assert!(struct_type.as_struct().unwrap().repr().is_none());
+}
+
+#[test]
+fn interpret_doc_comments() {
+    let struct_type = parse_declaration_checked(quote!(
+        /// First line
+        ///
+        ///     Indented "quoted" line
+        #[doc = "\tescaped\u{2764}"]
+        #[hello]
+        struct Hello {
+            /// Field doc
+            a: A,
+            b: B,
+        }
+    ));
+
+    assert_debug_snapshot!(struct_type.doc_string().unwrap());
+
+    let fields = match &struct_type.as_struct().unwrap().fields {
+        StructFields::Named(fields) => fields,
+        _ => unreachable!(),
+    };
+    assert_eq!(fields.fields[0].0.doc_string().unwrap(), "Field doc");
+    assert_eq!(fields.fields[1].0.doc_string(), None);
+}
+
 // =============
 // WHERE CLAUSES
 // =============
@@ -453,6 +955,143 @@ fn parse_enum_empty_where_clause() {
     assert_debug_snapshot!(enum_type);
 }
 
+#[test]
+fn interpret_where_clause_bounds() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<'a, 'b, T>(&'a T)
+        where
+            T: 'a + Debug + Into<Vec<u8>>,
+            'a: 'b;
+    ));
+
+    let where_clause = struct_type
+        .as_struct()
+        .unwrap()
+        .where_clause
+        .as_ref()
+        .unwrap();
+    let to_strings = |bounds: Vec<TokenStream>| -> Vec<_> {
+        bounds.iter().map(|bound| bound.to_string()).collect()
+    };
+    let lifetime_bounds: Vec<_> = where_clause
+        .items()
+        .map(|item| to_strings(item.lifetime_bounds()))
+        .collect();
+    let trait_bounds: Vec<_> = where_clause
+        .items()
+        .map(|item| to_strings(item.trait_bounds()))
+        .collect();
+
+    assert_eq!(lifetime_bounds, [vec!["'a"], vec!["'b"]]);
+    assert_eq!(trait_bounds, [vec!["Debug", "Into < Vec < u8 >>"], vec![]]);
+}
+
+#[test]
+fn parse_where_clause_assoc_types() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<T: Trait>(T)
+        where
+            T: Iterator<Item = u8>,
+            <T as Trait>::Assoc: Debug,
+            T::Output: Clone;
+    ));
+
+    let where_clause = struct_type
+        .as_struct()
+        .unwrap()
+        .where_clause
+        .as_ref()
+        .unwrap();
+    let items: Vec<_> = where_clause
+        .items()
+        .map(|item| {
+            (
+                TokenStream::from_iter(item.left_side.clone()).to_string(),
+                TokenStream::from_iter(item.bound.tokens.clone()).to_string(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        items,
+        [
+            ("T".to_string(), "Iterator < Item = u8 >".to_string()),
+            ("< T as Trait > :: Assoc".to_string(), "Debug".to_string()),
+            ("T :: Output".to_string(), "Clone".to_string()),
+        ]
+    );
+
+    let item: WhereClauseItem = "<T as Trait>::Assoc: Debug".parse().unwrap();
+    assert_tokens_eq(
+        TokenStream::from_iter(item.left_side),
+        quote!(<T as Trait>::Assoc),
+    );
+}
+
+#[test]
+fn parse_higher_ranked_bounds() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<F: for<'a> Fn(&'a str) -> &'a str, G>(F, G)
+        where
+            G: for<'a, 'b> FnMut(&'a u8, &'b u8) -> Vec<&'a u8>,
+            for<'c> &'c G: IntoIterator<Item = &'c u8>;
+    ));
+    let struct_type = struct_type.as_struct().unwrap();
+
+    let param_bounds: Vec<_> = struct_type
+        .generic_params
+        .as_ref()
+        .unwrap()
+        .params
+        .items()
+        .map(|param| param.bound.to_token_stream().to_string())
+        .collect();
+    assert_eq!(param_bounds, [": for < 'a > Fn (& 'a str) -> & 'a str", ""]);
+
+    let where_items: Vec<_> = struct_type
+        .where_clause
+        .as_ref()
+        .unwrap()
+        .items()
+        .map(|item| {
+            (
+                TokenStream::from_iter(item.left_side.clone()).to_string(),
+                TokenStream::from_iter(item.bound.tokens.clone()).to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        where_items,
+        [
+            (
+                "G".to_string(),
+                "for < 'a , 'b > FnMut (& 'a u8 , & 'b u8) -> Vec < & 'a u8 >".to_string()
+            ),
+            (
+                "for < 'c > & 'c G".to_string(),
+                "IntoIterator < Item = & 'c u8 >".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn parse_where_clause_item_fallible() {
+    let item = WhereClauseItem::try_parse(quote!(T: Clone + 'a)).unwrap();
+    assert_eq!(item.to_token_stream().to_string(), "T : Clone + 'a");
+
+    let item: WhereClauseItem = "T: Debug + Send".parse().unwrap();
+    assert_eq!(item.to_token_stream().to_string(), "T : Debug + Send");
+
+    let error = WhereClauseItem::try_parse(quote!(T Clone)).unwrap_err();
+    assert!(error.to_string().contains("expected colon"));
+
+    let error = "T: Into<(u8>".parse::<WhereClauseItem>().unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("cannot parse where-clause item"));
+}
+
 // ==============
 // GENERIC PARAMS
 // ==============
@@ -517,6 +1156,139 @@ fn parse_enum_empty_generic_params() {
     assert_debug_snapshot!(enum_type);
 }
 
+#[test]
+fn interpret_generic_param_kinds() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello<'a, T, const N: usize, 'b, U = i32>(&'a [T; N], &'b U);
+    ));
+
+    let kinded: Vec<_> = struct_type
+        .generic_params
+        .as_ref()
+        .unwrap()
+        .iter_kinded()
+        .map(|(kind, param)| (kind, param.name.to_string()))
+        .collect();
+
+    assert_eq!(
+        kinded,
+        [
+            (GenericParamKind::Lifetime, "a".to_string()),
+            (GenericParamKind::Type, "T".to_string()),
+            (GenericParamKind::Const, "N".to_string()),
+            (GenericParamKind::Lifetime, "b".to_string()),
+            (GenericParamKind::Type, "U".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn interpret_generic_param_bounds() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<'a, T: Debug + Clone + 'a, F: Fn(A, B) -> Vec<u8> + Send, U>(&'a T, F, U);
+    ));
+
+    let bounds: Vec<Vec<_>> = struct_type
+        .generic_params()
+        .unwrap()
+        .params
+        .items()
+        .map(|param| {
+            param
+                .bounds()
+                .iter()
+                .map(|bound| bound.to_string())
+                .collect()
+        })
+        .collect();
+
+    assert_eq!(
+        bounds,
+        [
+            vec![],
+            vec!["Debug", "Clone", "'a"],
+            vec!["Fn (A , B) -> Vec < u8 >", "Send"],
+            vec![],
+        ]
+    );
+}
+
+#[test]
+fn interpret_maybe_sized_params() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<T: ?Sized, U: Clone + ?std::marker::Sized, V: Sized, W: ?Unknown>(
+            Box<T>,
+            Box<U>,
+            V,
+            W,
+        );
+    ));
+
+    let params = &struct_type.generic_params().unwrap().params;
+    let maybe_sized: Vec<_> = params.items().map(|param| param.is_maybe_sized()).collect();
+    let relaxed_bounds: Vec<_> = params
+        .items()
+        .map(|param| {
+            let bound = param.bound.as_ref().unwrap();
+            let relaxed: Vec<_> = bound
+                .relaxed_bounds()
+                .iter()
+                .map(|b| b.to_string())
+                .collect();
+            relaxed.join(" + ")
+        })
+        .collect();
+
+    assert_eq!(maybe_sized, [true, true, false, false]);
+    assert_eq!(
+        relaxed_bounds,
+        ["Sized", "std :: marker :: Sized", "", "Unknown"]
+    );
+}
+
+#[test]
+fn parse_generic_params_defaults() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<T = Foo<A, B>, U: Clone = u32, const N: usize = 8, const M: u8 = { 1 + 2 }> {
+            a: [T; N],
+        }
+    ));
+
+    assert_debug_snapshot!(struct_type);
+}
+
+#[test]
+fn parse_generic_params_grouped_defaults() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<
+            const N: usize = { 1 << 3 },
+            const M: usize = { max(1, 2) },
+            const B: bool = { N > M },
+            T = (u8, Vec<u16>),
+        >;
+    ));
+
+    let defaults: Vec<_> = struct_type
+        .generic_params()
+        .unwrap()
+        .params
+        .items()
+        .map(|param| {
+            let default_value = param.default_value.as_ref().unwrap();
+            default_value.to_token_stream().to_string()
+        })
+        .collect();
+    assert_eq!(
+        defaults,
+        [
+            "{ 1 << 3 }",
+            "{ max (1 , 2) }",
+            "{ N > M }",
+            "(u8 , Vec < u16 >)"
+        ]
+    );
+}
+
 // ============
 // GENERIC ARGS
 // ============
@@ -553,21 +1325,120 @@ fn parse_inline_generic_args() {
     assert_debug_snapshot!(owned_args);
 }
 
-// ==================
-// ENUM VARIANT VALUE
-// ==================
-
 #[test]
-fn parse_complex_enum_variant() {
-    let enum_type_1 = parse_declaration(quote!(
-        enum Hello {
-            A = 1,
-            B(Foo, Bar) = 1 + 2 + 3,
-        }
+fn quote_inline_generic_args() {
+    let struct_decl = parse_declaration_checked(quote!(
+        struct Hello<'a: 'static, T: Debug = i32, const N: usize = 8> {}
     ));
-    let enum_type_2 = parse_declaration(quote!(
-        enum Hello {
-            A = 1,
+
+    let params = struct_decl.generic_params().unwrap();
+    let inline_args = params.as_inline_args();
+    let owned_args = inline_args.to_owned_args();
+
+    assert_quote_snapshot!(inline_args);
+    assert_quote_snapshot!(owned_args);
+}
+
+#[test]
+fn quote_turbofish_generic_args() {
+    let func = parse_declaration_checked(quote!(
+        fn hello<'a, T: Debug, const N: usize>(a: &'a [T; N]) {}
+    ));
+    let lifetime_only_func = parse_declaration_checked(quote!(
+        fn hello<'a>(a: &'a str) {}
+    ));
+    let non_generic_func = parse_declaration_checked(quote!(
+        fn hello() {}
+    ));
+
+    let turbofish = |func: &Declaration, include_lifetimes: bool| {
+        func.as_function()
+            .unwrap()
+            .get_turbofish_generic_args(include_lifetimes)
+            .to_token_stream()
+            .to_string()
+    };
+
+    assert_eq!(turbofish(&func, true), ":: < 'a , T , N , >");
+    assert_eq!(turbofish(&func, false), ":: < T , N , >");
+    assert_eq!(turbofish(&lifetime_only_func, true), ":: < 'a , >");
+    assert_eq!(turbofish(&lifetime_only_func, false), "");
+    assert_eq!(turbofish(&non_generic_func, true), "");
+}
+
+#[test]
+fn quote_impl_generics() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello<'a: 'static, T: Debug = i32, const N: usize = 8>(&'a [T; N])
+        where
+            T: Clone;
+    ));
+
+    let impl_generics = struct_type.impl_generics();
+    let ty_generics = struct_type.get_inline_generic_args();
+    let where_clause = &struct_type.where_clause;
+
+    assert_quote_snapshot!(quote!(
+        impl #impl_generics MyTrait for Hello #ty_generics #where_clause {}
+    ));
+
+    let unit_struct = parse_struct_declaration(quote!(
+        struct Hello;
+    ));
+    assert!(unit_struct.impl_generics().is_none());
+}
+
+#[test]
+fn quote_split_for_impl() {
+    let enum_type = parse_declaration_checked(quote!(
+        enum MyEnum<T: Clone, const N: usize = 4> {
+            A(T),
+        }
+    ));
+    let plain_enum = parse_declaration_checked(quote!(
+        enum MyEnum {
+            A,
+        }
+    ));
+    let impl_decl = parse_declaration_checked(quote!(
+        impl<T: Clone> MyTrait for Vec<T> where T: Debug {}
+    ));
+
+    let (impl_generics, ty_generics, where_clause) = enum_type.as_enum().unwrap().split_for_impl();
+    assert_quote_snapshot!(
+        quote!(impl #impl_generics MyTrait for MyEnum #ty_generics #where_clause {})
+    );
+
+    let (impl_generics, ty_generics, where_clause) = plain_enum.as_enum().unwrap().split_for_impl();
+    assert!(where_clause.is_none());
+    assert_eq!(
+        quote!(impl #impl_generics MyTrait for MyEnum #ty_generics {}).to_string(),
+        "impl MyTrait for MyEnum { }"
+    );
+
+    let impl_decl = impl_decl.as_impl().unwrap();
+    assert_eq!(
+        HasGenerics::generic_params(impl_decl).unwrap().params.len(),
+        1
+    );
+    assert!(HasGenerics::where_clause(impl_decl).is_some());
+}
+
+// ==================
+// ENUM VARIANT VALUE
+// ==================
+
+#[test]
+fn parse_complex_enum_variant() {
+    let enum_type_1 = parse_declaration(quote!(
+        enum Hello {
+            A = 1,
+            B(Foo, Bar) = 1 + 2 + 3,
+        }
+    ));
+    let enum_type_2 = parse_declaration(quote!(
+        enum Hello {
+            A = 1,
             B { foo: Foo, bar: Bar } = foo(bar),
         }
     ));
@@ -584,6 +1455,48 @@ fn parse_complex_enum_variant() {
     assert_debug_snapshot!(enum_type_3);
 }
 
+#[test]
+fn interpret_enum_discriminants() {
+    let enum_type = parse_declaration_checked(quote!(
+        #[repr(u8)]
+        enum Hello {
+            A = 1,
+            B,
+            C = 5,
+        }
+    ));
+    let enum_type = enum_type.as_enum().unwrap();
+
+    let discriminants: Vec<_> = enum_type
+        .variants
+        .items()
+        .map(|variant| {
+            variant
+                .discriminant()
+                .map(|discriminant| discriminant.value.to_string())
+        })
+        .collect();
+    assert_eq!(
+        discriminants,
+        [Some("1".to_string()), None, Some("5".to_string())]
+    );
+
+    let tk_equal = &enum_type.variants[0].0.discriminant().unwrap().tk_equal;
+    assert_eq!(tk_equal.as_char(), '=');
+    assert!(!enum_type.has_explicit_discriminants());
+
+    let explicit_enum = parse_declaration_checked(quote!(
+        enum Hello {
+            A = 1,
+            B(u8) = 2,
+        }
+    ));
+    assert!(explicit_enum
+        .as_enum()
+        .unwrap()
+        .has_explicit_discriminants());
+}
+
 // =================
 // TYPE CORNER CASES
 // =================
@@ -655,124 +1568,144 @@ fn parse_macro_in_where_clause() {
 
 #[test]
 fn parse_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn hello(a: i32, b: f32) -> String {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_empty_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn test_me() {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_generic_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn generic<T, B>(a: T) -> B {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_where_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn where_clause<T>() -> T
         where
             T: Debug,
         {
         }
-    ))
-    .unwrap();
-    let func_2 = parse_declaration(quote!(
+    ));
+    let func_2 = parse_declaration_checked(quote!(
         fn where_clause<T>()
         where
             T: Debug,
         {
         }
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
     assert_debug_snapshot!(func_2);
 }
 
+#[test]
+fn parse_where_fn_after_return_ty() {
+    let with_body = parse_declaration_checked(quote!(
+        fn foo<T>() -> Vec<T>
+        where
+            T: Default + Clone,
+        {
+            vec![T::default()]
+        }
+    ));
+    let prototype = parse_declaration_checked(quote!(
+        fn foo<T>() -> T
+        where
+            T: Default;
+    ));
+
+    for (func, return_ty) in [(with_body, "Vec < T >"), (prototype, "T")] {
+        let func = match func {
+            Declaration::Function(func) => func,
+            _ => unreachable!(),
+        };
+        let where_clause = func.where_clause.as_ref().unwrap();
+
+        assert_eq!(
+            func.return_ty.unwrap().to_token_stream().to_string(),
+            return_ty
+        );
+        assert_eq!(where_clause.items().count(), 1);
+        assert_eq!(func.body.is_some(), func.tk_semicolon.is_none());
+    }
+}
+
 #[test]
 fn parse_attr_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         #[my_attr]
         fn my_attr_fn(a: i32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_visi_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub fn visibility(b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_default_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub default fn default_fn(b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_const_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub const fn const_fn(b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_async_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub async fn async_fn(b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_unsafe_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub unsafe fn unsafe_fn(b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_extern_abi_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub extern "C" fn extern_fn(b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
@@ -780,72 +1713,185 @@ fn parse_extern_abi_fn() {
 #[test]
 fn parse_extern_fn() {
     #[rustfmt::skip] // would add "C"
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub extern fn extern_fn(b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_all_kw_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub default const async unsafe extern "C" fn all_kw(b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
+#[test]
+fn parse_qualifier_order_fn() {
+    let const_unsafe_fn = parse_declaration_checked(quote!(
+        const unsafe fn foo() {}
+    ));
+    let const_fn = parse_declaration_checked(quote!(
+        const fn bar() {}
+    ));
+    let unsafe_extern_fn = parse_declaration_checked(quote!(
+        pub(crate) unsafe extern "C" fn baz() {}
+    ));
+
+    assert_eq!(
+        const_unsafe_fn.to_token_stream().to_string(),
+        "const unsafe fn foo () { }"
+    );
+    assert_eq!(
+        const_fn.to_token_stream().to_string(),
+        "const fn bar () { }"
+    );
+    assert_eq!(
+        unsafe_extern_fn.to_token_stream().to_string(),
+        "pub (crate) unsafe extern \"C\" fn baz () { }"
+    );
+
+    let func = const_unsafe_fn.as_function().unwrap();
+    assert!(func.is_const());
+    assert!(func.is_unsafe());
+}
+
 #[test]
 fn parse_param_attr_fn() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         pub async fn visibility(#[my_attr] b: f32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_fn_body() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn hello_world(a: i32, b: f32) -> String {
             println!("hello world")
         }
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
+#[test]
+fn interpret_fn_attributes() {
+    let func = parse_declaration_checked(quote!(
+        #[inline]
+        #[allow(unused)]
+        #[doc = "hello"]
+        fn hello(
+            #[my_attr] a: i32,
+            #[cfg(test)]
+            #[other]
+            b: i32,
+        ) {
+            #![allow(dead_code)]
+        }
+    ));
+    let func = func.as_function().unwrap();
+
+    let attributes: Vec<_> = func
+        .attributes()
+        .iter()
+        .map(|attribute| attribute.get_single_path_segment().unwrap().to_string())
+        .collect();
+    assert_eq!(attributes, ["inline", "allow", "doc"]);
+
+    let param_attributes: Vec<_> = func
+        .params
+        .items()
+        .map(|param| match param {
+            FnParam::Typed(param) => param.attributes().len(),
+            FnParam::Receiver(param) => param.attributes().len(),
+        })
+        .collect();
+    assert_eq!(param_attributes, [1, 2]);
+
+    // Inner attributes are left in the body
+    assert!(func
+        .body_tokens()
+        .unwrap()
+        .to_string()
+        .starts_with("# ! [allow"));
+}
+
+#[test]
+fn interpret_fn_param_simple_ident() {
+    let func = parse_declaration_checked(quote!(
+        fn hello(self: Pin<&mut Self>, a: u8, mut b: u16, #[attr] c: (u8, u8)) {}
+    ));
+
+    let names: Vec<_> = func
+        .as_function()
+        .unwrap()
+        .params
+        .items()
+        .map(|param| param.simple_ident().map(|name| name.to_string()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            None,
+            Some("a".to_string()),
+            Some("b".to_string()),
+            Some("c".to_string())
+        ]
+    );
+}
+
+#[test]
+fn edit_fn_body() {
+    let func = parse_declaration_checked(quote!(
+        fn hello(a: i32) -> i32 {
+            a + 1
+        }
+    ));
+    let prototype = parse_declaration_checked(quote!(
+        fn hello(a: i32) -> i32;
+    ));
+    let func = func.as_function().unwrap();
+    let prototype = prototype.as_function().unwrap();
+
+    assert_eq!(func.body_tokens().unwrap().to_string(), "a + 1");
+    assert!(prototype.body_tokens().is_none());
+
+    let body = func.body_tokens().unwrap();
+    let func = func.clone().with_body_tokens(quote!(dbg!(#body)));
+    let prototype = prototype.clone().with_body_tokens(quote!(a));
+
+    assert_quote_snapshot!(func);
+    assert_quote_snapshot!(prototype);
+}
+
 #[test]
 fn parse_fn_prototype() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn prototype(a: i32, b: f32) -> String;
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_fn_mut_param() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn prototype(a: i32, mut b: f32) -> String;
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
 
 #[test]
 fn parse_fn_lifetimes() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn prototype<'a>(a: &'a mut i32) -> &'a String;
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
@@ -854,10 +1900,9 @@ fn parse_fn_lifetimes() {
 #[test]
 #[should_panic]
 fn parse_fn_pattern_arg() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn foobar((a, b): (i32, i32)) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
@@ -866,10 +1911,9 @@ fn parse_fn_pattern_arg() {
 #[test]
 #[should_panic]
 fn parse_fn_c_variadics() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn foobar(a: i32, ...) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
@@ -878,10 +1922,9 @@ fn parse_fn_c_variadics() {
 #[test]
 #[should_panic]
 fn parse_fn_no_pattern() {
-    let func = parse_declaration(quote!(
+    let func = parse_declaration_checked(quote!(
         fn foobar(i32) {}
-    ))
-    .unwrap();
+    ));
 
     assert_debug_snapshot!(func);
 }
@@ -907,6 +1950,43 @@ fn parse_fn_self_param() {
     assert_debug_snapshot!(func_ref_mut_self);
 }
 
+#[test]
+fn parse_fn_typed_self_param() {
+    let func_box_self = parse_declaration_checked(quote!(
+        fn foobar(self: Box<Self>) {}
+    ));
+    let func_mut_pin_self = parse_declaration_checked(quote!(
+        fn foobar(mut self: Pin<&mut Self>, other: i32) {}
+    ));
+
+    assert_debug_snapshot!(func_box_self);
+    assert_debug_snapshot!(func_mut_pin_self);
+}
+
+#[test]
+fn parse_fn_complex_return_ty() {
+    let func_result = parse_declaration_checked(quote!(
+        fn foobar() -> Result<T, E> {}
+    ));
+    let func_impl_trait = parse_declaration_checked(quote!(
+        fn foobar() -> impl Iterator<Item = u8> {}
+    ));
+    let func_ref_slice = parse_declaration_checked(quote!(
+        fn foobar<'a>(a: &'a i32) -> &'a mut [u8];
+    ));
+    let func_nested_arrow = parse_declaration_checked(quote!(
+        fn foobar() -> Box<dyn Fn(i32) -> i32>
+        where
+            Self: Sized;
+    ));
+
+    let return_ty = |func: &Declaration| func.as_function().unwrap().return_ty.clone();
+    assert_quote_snapshot!(return_ty(&func_result));
+    assert_quote_snapshot!(return_ty(&func_impl_trait));
+    assert_quote_snapshot!(return_ty(&func_ref_slice));
+    assert_quote_snapshot!(return_ty(&func_nested_arrow));
+}
+
 // ============
 // TYPE EDITING
 // ============
@@ -1033,73 +2113,384 @@ fn add_const_param() {
 }
 
 #[test]
-fn add_where_item() {
-    let basic_type = parse_struct_declaration(quote!(
-        struct Hello {
-            a: A,
-            b: B,
-        }
-    ));
-    let type_with_args = parse_struct_declaration(quote!(
-        struct Hello<A, B>
-        where
-            A: Clone,
-            B: Clone,
-        {
-            a: A,
-            b: B,
+fn add_const_param_with_default() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello<T> {
+            a: [T; N],
         }
+    ))
+    .with_param(GenericParam::const_param_with_default(
+        "N",
+        quote!(usize).into_iter().collect(),
+        quote!(8).into_iter().collect(),
     ));
 
-    let basic_type = basic_type.with_where_item(WhereClauseItem::parse(quote!(Self: Sized)));
-    let type_with_args =
-        type_with_args.with_where_item(WhereClauseItem::parse(quote!(Self: Sized)));
-
-    assert_quote_snapshot!(basic_type);
-    assert_quote_snapshot!(type_with_args);
+    assert_quote_snapshot!(struct_type);
 }
 
-// =================
-// IMPL DECLARATIONS
-// =================
-
 #[test]
-fn parse_impl_inherent() {
-    let expr = quote!(
-        #[outer]
-        impl MyStruct {
-            #![inner]
-
-            fn new(i: i32, b: bool) -> Self {
-                Self {}
-            }
-
-            #[attr]
-            pub(crate) fn set_value(&mut self, s: String) {}
+fn edit_punctuated_params() {
+    let mut struct_type = parse_struct_declaration(quote!(
+        struct Hello<A, B>(A, B);
+    ));
 
-            pub const CONSTANT: i8 = 24 + 7;
-        }
+    let params = &mut struct_type.generic_params.as_mut().unwrap().params;
+    params.get_mut(1).unwrap().name = quote::format_ident!("C");
+    params.insert(0, GenericParam::lifetime("a"), None);
+    assert_eq!(params.len(), 3);
+    assert!(params.get(3).is_none());
+
+    let names: Vec<_> = (&*params)
+        .into_iter()
+        .map(|param| param.name.to_string())
+        .collect();
+    assert_eq!(names, ["a", "A", "C"]);
+    assert_eq!(
+        struct_type.to_token_stream().to_string(),
+        "struct Hello < 'a , A , C > (A , B) ;"
     );
-
-    let impl_decl = parse_declaration_checked(expr);
-    assert_debug_snapshot!(impl_decl);
 }
 
 #[test]
-fn parse_impl_inherent_generic() {
-    let expr = quote!(
-        impl<'a, T: Clone, const N: i8> structs::MyStruct<'a, T, N> {}
+fn parse_generic_param_attributes() {
+    let struct_type = parse_declaration_str(
+        "struct Hello<#[a] 'a, #[b] #[c] T: Clone, #[d] const N: usize = 4>(&'a [T; N]);",
+    )
+    .unwrap();
+    let params: Vec<_> = struct_type
+        .generic_params()
+        .unwrap()
+        .params
+        .items()
+        .collect();
+
+    let names = |param: &GenericParam| -> Vec<String> {
+        param
+            .attributes()
+            .iter()
+            .map(|attribute| attribute.get_single_path_segment().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(names(params[0]), ["a"]);
+    assert_eq!(names(params[1]), ["b", "c"]);
+    assert_eq!(names(params[2]), ["d"]);
+    assert!(params[0].is_lifetime());
+    assert!(params[2].is_const());
+    assert!(params[1].has_attribute("c"));
+
+    assert_tokens_eq(
+        struct_type.generic_params().unwrap().to_token_stream(),
+        quote!(<#[a] 'a, #[b] #[c] T: Clone, #[d] const N: usize = 4>),
     );
 
-    let impl_decl = parse_declaration_checked(expr);
-    assert_debug_snapshot!(impl_decl);
+    let mut walked = Vec::new();
+    struct_type.walk_attributes(|attribute| walked.push(attribute.path[0].to_string()));
+    assert_eq!(walked, ["a", "b", "c", "d"]);
+
+    let without_attributes =
+        parse_declaration_str("struct Hello<'a, T: Clone, const N: usize = 4>(&'a [T; N]);")
+            .unwrap();
+    assert!(struct_type
+        .as_struct()
+        .unwrap()
+        .eq_ignoring_attrs(without_attributes.as_struct().unwrap()));
+
+    let attribute = params[0].attributes()[0].clone();
+    let param = GenericParam::ty("T").with_attribute(attribute);
+    assert_tokens_eq(
+        param.to_token_stream(),
+        quote!(
+            #[a]
+            T
+        ),
+    );
 }
 
 #[test]
-fn parse_impl_trait() {
-    let expr = quote!(
-        #[outer]
-        impl MyTrait for MyStruct {
+fn impl_generics_drop_param_attributes() {
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<#[a] 'a, #[b] T: Clone = i32, #[c] const N: usize = 4>(&'a [T; N]);
+    ));
+    let struct_type = struct_type.as_struct().unwrap();
+
+    let (split_generics, _, _) = struct_type.split_for_impl();
+    let impl_generics = struct_type.impl_generics();
+    assert_tokens_eq(
+        split_generics.to_token_stream(),
+        quote!(<'a, T: Clone, const N: usize,>),
+    );
+    assert_tokens_eq(
+        impl_generics.to_token_stream(),
+        quote!(<'a, T: Clone, const N: usize>),
+    );
+}
+
+#[test]
+fn build_generic_params_from_iter() {
+    let params: GenericParamList = vec![
+        GenericParam::ty("T"),
+        GenericParam::lifetime("a"),
+        GenericParam::bounded_ty("U", quote!(Clone).into_iter().collect()),
+        GenericParam::lifetime("b"),
+    ]
+    .into_iter()
+    .collect();
+    let struct_type =
+        Struct::new("Hello").with_tuple_field(TupleField::new(TyExpr::from_tokens(quote!(&'a T))));
+    let struct_type = Struct {
+        generic_params: Some(params),
+        ..struct_type
+    };
+
+    assert_eq!(
+        struct_type.to_token_stream().to_string(),
+        "struct Hello < 'a , 'b , T , U : Clone > (& 'a T) ;"
+    );
+
+    let empty_params: GenericParamList = std::iter::empty().collect();
+    assert!(empty_params.params.is_empty());
+}
+
+#[test]
+fn edit_generic_param_bound() {
+    let mut struct_type = parse_struct_declaration(quote!(
+        struct Hello<T: Clone + Debug, U, const N: usize>(T, U);
+    ));
+    let params = &mut struct_type.generic_params.as_mut().unwrap().params;
+
+    let bound = params[0].0.bound().unwrap();
+    assert_eq!(bound.tk_colon.as_char(), ':');
+    assert_eq!(
+        TokenStream::from_iter(bound.tokens.clone()).to_string(),
+        "Clone + Debug"
+    );
+    assert!(params[1].0.bound().is_none());
+
+    let t_bound = params[0].0.set_bound(None);
+    params[1].0.set_bound(t_bound);
+    assert_eq!(
+        TokenStream::from_iter(params[2].0.bound().unwrap().tokens.clone()).to_string(),
+        "usize"
+    );
+
+    assert_eq!(
+        struct_type.to_token_stream().to_string(),
+        "struct Hello < T , U : Clone + Debug , const N : usize > (T , U) ;"
+    );
+}
+
+#[test]
+fn edit_trailing_punct() {
+    let with_comma = parse_declaration_str("struct Hello { a: A, b: B, }").unwrap();
+    let without_comma = parse_declaration_str("struct Hello<T, U> { a: A, b: B }").unwrap();
+
+    let named_fields = |declaration: &Declaration| match &declaration.as_struct().unwrap().fields {
+        StructFields::Named(named_fields) => named_fields.fields.clone(),
+        _ => unreachable!(),
+    };
+    assert!(named_fields(&with_comma).has_trailing_punct());
+    assert!(!named_fields(&without_comma).has_trailing_punct());
+    assert!(!without_comma
+        .generic_params()
+        .unwrap()
+        .params
+        .has_trailing_punct());
+
+    let mut struct_type = without_comma.as_struct().unwrap().clone();
+    match &mut struct_type.fields {
+        StructFields::Named(named_fields) => named_fields.fields.set_trailing_punct(true),
+        _ => unreachable!(),
+    }
+    assert_eq!(
+        struct_type.to_token_stream().to_string(),
+        "struct Hello < T , U > { a : A , b : B , }"
+    );
+}
+
+#[test]
+fn build_struct() {
+    let attribute = parse_struct_declaration(quote!(
+        #[serde(skip)]
+        struct Attr;
+    ))
+    .attributes
+    .remove(0);
+
+    let unit_struct = Struct::new("Hello");
+    let named_struct = Struct::new("Hello")
+        .with_named_field(
+            NamedField::new("a", TyExpr::from_tokens(quote!(u32))).with_vis(VisMarker::pub_vis()),
+        )
+        .with_named_field(
+            NamedField::new("b", TyExpr::from_tokens(quote!(Vec<u8>)))
+                .with_attribute(attribute.clone()),
+        );
+    let tuple_struct = Struct::new("Hello")
+        .with_tuple_field(
+            TupleField::new(TyExpr::from_tokens(quote!(u32))).with_vis(VisMarker::pub_vis()),
+        )
+        .with_tuple_field(
+            TupleField::new(TyExpr::from_tokens(quote!(Vec<u8>))).with_attribute(attribute),
+        );
+
+    assert_quote_snapshot!(unit_struct);
+    assert_quote_snapshot!(named_struct);
+    assert_quote_snapshot!(tuple_struct);
+}
+
+#[test]
+fn edit_declaration_name() {
+    let mut struct_type = parse_declaration_checked(quote!(
+        struct Foo<T> {
+            a: T,
+        }
+    ));
+    let mut impl_decl = parse_declaration_checked(quote!(
+        impl Foo {}
+    ));
+
+    let previous_name = struct_type.set_name(quote::format_ident!("FooBuilder"));
+    assert_eq!(previous_name.unwrap().to_string(), "Foo");
+    assert_eq!(struct_type.name().unwrap().to_string(), "FooBuilder");
+
+    assert!(impl_decl.set_name(quote::format_ident!("Bar")).is_none());
+    assert_eq!(impl_decl.to_token_stream().to_string(), "impl Foo { }");
+
+    let func = parse_declaration_checked(quote!(
+        fn foo() {}
+    ));
+    let func = func
+        .as_function()
+        .unwrap()
+        .clone()
+        .with_name(quote::format_ident!("bar"));
+    assert_eq!(func.to_token_stream().to_string(), "fn bar () { }");
+}
+
+#[test]
+fn build_enum() {
+    let empty_enum = Enum::new("Hello");
+    let enum_type = Enum::new("Hello")
+        .with_variant(EnumVariant::unit("A"))
+        .with_variant(EnumVariant::tuple(
+            "B",
+            vec![
+                TupleField::new(TyExpr::from_tokens(quote!(u32))),
+                TupleField::new(TyExpr::from_tokens(quote!(Vec<u8>))),
+            ],
+        ))
+        .with_variant(EnumVariant::named(
+            "C",
+            vec![NamedField::new("c", TyExpr::from_tokens(quote!(char)))],
+        ));
+
+    assert_quote_snapshot!(empty_enum);
+    assert_quote_snapshot!(enum_type);
+}
+
+#[test]
+fn add_where_item() {
+    let basic_type = parse_struct_declaration(quote!(
+        struct Hello {
+            a: A,
+            b: B,
+        }
+    ));
+    let type_with_args = parse_struct_declaration(quote!(
+        struct Hello<A, B>
+        where
+            A: Clone,
+            B: Clone,
+        {
+            a: A,
+            b: B,
+        }
+    ));
+
+    let basic_type = basic_type.with_where_item(WhereClauseItem::parse(quote!(Self: Sized)));
+    let type_with_args =
+        type_with_args.with_where_item(WhereClauseItem::parse(quote!(Self: Sized)));
+
+    assert_quote_snapshot!(basic_type);
+    assert_quote_snapshot!(type_with_args);
+}
+
+#[test]
+fn create_derive_where_clause() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello<'a, T, U, const N: usize>(&'a T, [U; N])
+        where
+            T: Debug,
+            U: Clone;
+    ));
+
+    let where_clause = struct_type.create_derive_where_clause(quote!(Clone));
+    let filtered_where_clause =
+        struct_type.create_derive_where_clause_for(quote!(Clone), |param| param.name != "T");
+
+    assert_quote_snapshot!(where_clause);
+    assert_quote_snapshot!(filtered_where_clause);
+}
+
+#[test]
+fn remove_param() {
+    let mut type_with_args = parse_struct_declaration(quote!(
+        struct Hello<'a, 'marker, T: Clone, const N: usize> {
+            a: &'a [T; N],
+        }
+    ));
+
+    let params = type_with_args.generic_params.as_mut().unwrap();
+    let lifetime = params.remove_param("marker").unwrap();
+    let const_param = params.remove_param("N").unwrap();
+    assert!(params.remove_param("U").is_none());
+
+    assert!(lifetime.is_lifetime());
+    assert!(const_param.is_const());
+    assert_quote_snapshot!(type_with_args);
+}
+
+// =================
+// IMPL DECLARATIONS
+// =================
+
+#[test]
+fn parse_impl_inherent() {
+    let expr = quote!(
+        #[outer]
+        impl MyStruct {
+            #![inner]
+
+            fn new(i: i32, b: bool) -> Self {
+                Self {}
+            }
+
+            #[attr]
+            pub(crate) fn set_value(&mut self, s: String) {}
+
+            pub const CONSTANT: i8 = 24 + 7;
+        }
+    );
+
+    let impl_decl = parse_declaration_checked(expr);
+    assert_debug_snapshot!(impl_decl);
+}
+
+#[test]
+fn parse_impl_inherent_generic() {
+    let expr = quote!(
+        impl<'a, T: Clone, const N: i8> structs::MyStruct<'a, T, N> {}
+    );
+
+    let impl_decl = parse_declaration_checked(expr);
+    assert_debug_snapshot!(impl_decl);
+}
+
+#[test]
+fn parse_impl_trait() {
+    let expr = quote!(
+        #[outer]
+        impl MyTrait for MyStruct {
             #![inner]
             #![inner2]
 
@@ -1138,6 +2529,88 @@ fn parse_impl_trait_generic() {
     assert_debug_snapshot!(impl_decl);
 }
 
+#[test]
+fn parse_impl_accessors() {
+    let inherent_impl = parse_declaration_checked(quote!(
+        impl MyStruct {
+            #![inner]
+            fn new() -> Self {}
+        }
+    ));
+    let trait_impl = parse_declaration_checked(quote!(
+        impl<T> std::fmt::Debug for MyStruct<T> {}
+    ));
+
+    let inherent_impl = inherent_impl.as_impl().unwrap();
+    let trait_impl = trait_impl.as_impl().unwrap();
+
+    assert!(!inherent_impl.is_trait_impl());
+    assert!(inherent_impl.trait_path().is_none());
+    assert_quote_snapshot!(inherent_impl.body_tokens());
+
+    assert!(trait_impl.is_trait_impl());
+    assert_debug_snapshot!(trait_impl.trait_path().unwrap());
+    assert!(trait_impl.body_tokens().is_empty());
+}
+
+// =============
+// EXTERN BLOCKS
+// =============
+
+#[test]
+fn parse_extern_block() {
+    let expr = quote!(
+        #[link(name = "c")]
+        extern "C" {
+            #![inner]
+
+            pub fn abs(x: i32) -> i32;
+
+            #[attr]
+            fn strlen(s: *const u8) -> usize;
+
+            static mut errno: i32;
+
+            type Opaque;
+        }
+    );
+
+    let extern_decl = parse_declaration_checked(expr);
+    assert_debug_snapshot!(extern_decl);
+}
+
+#[test]
+fn parse_extern_block_variants() {
+    let unsafe_block = parse_declaration_checked(quote!(
+        unsafe extern "C" {}
+    ));
+    // Built from a string, since rustfmt would insert the "C" ABI
+    let no_abi_block = parse_declaration_checked("extern { fn foo(); }".parse().unwrap());
+
+    let unsafe_block = unsafe_block.as_extern_block().unwrap();
+    assert!(unsafe_block.tk_unsafe.is_some());
+    assert_eq!(
+        unsafe_block.extern_abi.as_ref().unwrap().to_string(),
+        "\"C\""
+    );
+    assert!(unsafe_block.body_items.is_empty());
+
+    let no_abi_block = no_abi_block.as_extern_block().unwrap();
+    assert!(no_abi_block.extern_abi.is_none());
+    match &no_abi_block.body_items[..] {
+        [ExternMember::Function(function)] => assert!(!function.has_body()),
+        items => panic!("expected a single function, got {:?}", items),
+    }
+}
+
+#[test]
+fn parse_extern_fn_is_not_block() {
+    let func = parse_declaration_checked(quote!(
+        extern "C" fn foo() {}
+    ));
+    assert!(func.as_function().is_some());
+}
+
 // =================
 // TYPE DECLARATIONS
 // =================
@@ -1163,6 +2636,20 @@ fn parse_type_complex() {
     assert_debug_snapshot!(ty_decl);
 }
 
+#[test]
+fn parse_type_generic() {
+    let expr = quote!(
+        pub type MyResult<'a, T>
+        where
+            T: Clone,
+        = Result<&'a T, MyError>;
+    );
+
+    let ty_decl = parse_declaration_checked(expr);
+    assert_eq!(ty_decl.generic_params().unwrap().params.len(), 2);
+    assert_debug_snapshot!(ty_decl);
+}
+
 // =====================
 // CONSTANT DECLARATIONS
 // =====================
@@ -1199,6 +2686,17 @@ fn parse_constant_expressionless() {
     assert_debug_snapshot!(const_decl);
 }
 
+#[test]
+fn parse_static_mut() {
+    let expr = quote!(
+        pub static mut COUNTER: std::sync::atomic::AtomicU32 = AtomicU32::new(0);
+    );
+
+    let static_decl = parse_declaration_checked(expr);
+    assert_eq!(static_decl.name().unwrap().to_string(), "COUNTER");
+    assert_debug_snapshot!(static_decl);
+}
+
 // =====================
 // TYPE PATH EXPRESSIONS
 // =====================
@@ -1239,59 +2737,946 @@ fn interpret_ty_expr_invalid_as_path() {
     assert!(invalid.is_none())
 }
 
-// ================
-// MOD DECLARATIONS
-// ================
-
 #[test]
-fn parse_mod() {
-    let expr = quote! {
-        #[path = "some/module"]
-        mod one_module {
-            #![allow(clippy::iter_with_drain)]
-
-            use std::clone::Clone as Clown;
-            use std::cell::{Cell, RefCell};
+fn interpret_ty_expr_path_segments() {
+    let ty_expr = |tokens: TokenStream| TyExpr {
+        tokens: tokens.into_iter().collect(),
+    };
 
-            pub struct MyStruct {
-                field: i32,
-            }
-            impl MyStruct {}
-            impl MyTrait for MyStruct {}
+    let path = ty_expr(quote!(std::collections::HashMap<K, V>))
+        .as_path()
+        .expect("as_path()");
+    let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let last_args = path.segments.last().unwrap().generic_args.as_ref();
+
+    assert!(path.segments[0].tk_separator_colons.is_none());
+    assert_eq!(segments, ["std", "collections", "HashMap"]);
+    assert_eq!(
+        last_args.unwrap().to_token_stream().to_string(),
+        "< K , V >"
+    );
 
-            #[derive(Copy, Clone)]
-            enum Enum {
-                Variant,
-            }
+    assert!(ty_expr(quote!(&T)).as_path().is_none());
+    assert!(ty_expr(quote!((A, B))).as_path().is_none());
+    assert!(ty_expr(quote!(dyn Trait)).as_path().is_none());
+}
 
-            fn f() -> bool { true }
+#[test]
+fn interpret_ty_expr_option_inner() {
+    let option_inner = |tokens: TokenStream| {
+        let ty_expr = TyExpr {
+            tokens: tokens.into_iter().collect(),
+        };
+        ty_expr
+            .option_inner()
+            .map(|inner| inner.to_token_stream().to_string())
+    };
 
-            pub use crate::{A, self, b::c};
+    assert_eq!(option_inner(quote!(Option<T>)).unwrap(), "T");
+    assert_eq!(option_inner(quote!(Option::<T>)).unwrap(), "T");
+    assert_eq!(
+        option_inner(quote!(std::option::Option<Vec<T>>)).unwrap(),
+        "Vec < T >"
+    );
+    assert_eq!(
+        option_inner(quote!(::core::option::Option<&'a str>)).unwrap(),
+        "& 'a str"
+    );
 
-            const C: i32 = -8 * 2;
+    assert!(option_inner(quote!(Vec<T>)).is_none());
+    assert!(option_inner(quote!(Option)).is_none());
+    assert!(option_inner(quote!(my::option::Option<T>)).is_none());
+    assert!(option_inner(quote!(&Option<T>)).is_none());
+}
 
-            pub static MUTEX: std::sync::Mutex<i32> = std::sync::Mutex::new(0);
+#[test]
+fn interpret_struct_fields_shape() {
+    let unit_fields = parse_struct_declaration(quote!(
+        struct Hello;
+    ))
+    .fields;
+    let empty_fields = parse_struct_declaration(quote!(
+        struct Hello {}
+    ))
+    .fields;
+    let named_fields = parse_struct_declaration(quote!(
+        struct Hello {
+            a: A,
+            b: B,
+            c: C,
+        }
+    ))
+    .fields;
 
-            type MyType = Rc<RefCell<MyStruct>>;
+    assert!(unit_fields.is_unit());
+    assert!(unit_fields.is_empty());
+    assert_eq!(unit_fields.len(), 0);
 
-            mod nested_mod {
-                fn g() {}
-            }
+    assert!(empty_fields.is_named());
+    assert!(empty_fields.is_empty());
 
-            mod empty_mod {}
-            pub(crate) mod foreign_mod;
+    assert!(named_fields.is_named());
+    assert!(!named_fields.is_tuple());
+    assert!(!named_fields.is_empty());
+    assert_eq!(named_fields.len(), 3);
+}
 
-            #[contain_it]
-            unsafe mod hazard_mod {}
+#[test]
+fn interpret_struct_fields_delimiter() {
+    let unit_fields = parse_struct_declaration(quote!(
+        struct Hello;
+    ))
+    .fields;
+    let tuple_fields = parse_struct_declaration(quote!(
+        struct Hello(A, B);
+    ))
+    .fields;
+    let named_fields = parse_struct_declaration(quote!(
+        struct Hello {
+            a: A,
         }
-    };
+    ))
+    .fields;
 
-    let mod_decl = parse_declaration_checked(expr);
-    assert_debug_snapshot!(mod_decl);
+    assert_eq!(unit_fields.delimiter(), None);
+    assert_eq!(tuple_fields.delimiter(), Some(Delimiter::Parenthesis));
+    assert_eq!(named_fields.delimiter(), Some(Delimiter::Brace));
 }
 
-// ==================
-// TRAIT DECLARATIONS
+#[test]
+fn interpret_struct_shape() {
+    let unit_struct = parse_struct_declaration(quote!(
+        struct Hello;
+    ));
+    let empty_struct = parse_struct_declaration(quote!(
+        struct Hello {}
+    ));
+    let tuple_struct = parse_struct_declaration(quote!(
+        struct Hello();
+    ));
+
+    assert!(unit_struct.is_unit_struct());
+    assert!(!unit_struct.is_named_struct());
+    assert!(empty_struct.is_named_struct());
+    assert!(!empty_struct.is_unit_struct());
+    assert!(tuple_struct.is_tuple_struct());
+    assert!(!tuple_struct.is_unit_struct());
+}
+
+#[test]
+fn interpret_fields_iter() {
+    let named_struct = parse_struct_declaration(quote!(
+        struct Hello {
+            #[attr]
+            pub a: A,
+            b: B,
+        }
+    ));
+    let unit_struct = parse_struct_declaration(quote!(
+        struct Hello;
+    ));
+
+    let fields: Vec<_> = named_struct
+        .fields_iter()
+        .map(|field| {
+            (
+                field.name().map(|name| name.to_string()),
+                field.ty().to_token_stream().to_string(),
+                field.attributes().len(),
+                field.vis().is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            (Some("a".to_string()), "A".to_string(), 1, true),
+            (Some("b".to_string()), "B".to_string(), 0, false),
+        ]
+    );
+
+    assert_eq!(unit_struct.fields_iter().count(), 0);
+}
+
+#[test]
+fn interpret_field_types_iter() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello {
+            a: u8,
+            b: Option<String>,
+            c: (),
+        }
+    ));
+
+    let collected: Vec<_> = struct_type.field_types().into_iter().collect();
+    let iterated: Vec<_> = struct_type.field_types_iter().collect();
+    assert_eq!(collected.len(), iterated.len());
+    for (a, b) in collected.iter().zip(&iterated) {
+        assert!(std::ptr::eq(*a, *b));
+    }
+
+    let optional_count = struct_type
+        .field_types_iter()
+        .filter(|ty| ty.option_inner().is_some())
+        .count();
+    assert_eq!(optional_count, 1);
+
+    let unit_struct = parse_struct_declaration(quote!(
+        struct Unit;
+    ));
+    assert_eq!(unit_struct.field_types_iter().count(), 0);
+}
+
+#[test]
+fn interpret_get_field() {
+    let named_struct = parse_struct_declaration(quote!(
+        struct Hello {
+            a: A,
+            r#b: B,
+        }
+    ));
+    let tuple_struct = parse_struct_declaration(quote!(
+        struct Hello(A, B);
+    ));
+
+    let field_ty = |ty: &TyExpr| ty.to_token_stream().to_string();
+
+    assert_eq!(field_ty(&named_struct.get_field("a").unwrap().ty), "A");
+    assert_eq!(field_ty(&named_struct.get_field("b").unwrap().ty), "B");
+    assert_eq!(field_ty(&named_struct.get_field("r#b").unwrap().ty), "B");
+    assert_eq!(field_ty(&named_struct.get_field("r#a").unwrap().ty), "A");
+    assert!(named_struct.get_field("c").is_none());
+    assert!(named_struct.get_tuple_field(0).is_none());
+
+    assert_eq!(field_ty(&tuple_struct.get_tuple_field(1).unwrap().ty), "B");
+    assert!(tuple_struct.get_tuple_field(2).is_none());
+    assert!(tuple_struct.get_field("0").is_none());
+}
+
+#[test]
+fn interpret_enum_discriminant_value() {
+    let enum_type = parse_declaration_checked(quote!(
+        enum Hello {
+            A = 1,
+            B = (1 << 4),
+            C,
+        }
+    ));
+    let variants: Vec<_> = enum_type.as_enum().unwrap().variants.items().collect();
+
+    let value = variants[0].discriminant().unwrap();
+    assert_eq!(value.tk_equal.as_char(), '=');
+    assert_tokens_eq(value.value_tokens(), quote!(1));
+
+    let value = variants[1].discriminant().unwrap();
+    assert_tokens_eq(value.value_tokens(), quote!((1 << 4)));
+    assert!(
+        matches!(&value.value, proc_macro2::TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis)
+    );
+
+    assert!(variants[2].discriminant().is_none());
+}
+
+#[test]
+fn interpret_enum_variant_fields() {
+    let enum_type = parse_declaration_checked(quote!(
+        enum Hello {
+            A,
+            B(#[attr] u8, pub Vec<u8>),
+            C { c: char },
+        }
+    ));
+    let variants: Vec<_> = enum_type.as_enum().unwrap().variants.items().collect();
+
+    let names = |variant: &EnumVariant| -> Vec<_> { variant.field_names().collect() };
+    let types = |variant: &EnumVariant| -> Vec<_> {
+        variant
+            .field_types()
+            .map(|ty| ty.to_token_stream().to_string())
+            .collect()
+    };
+
+    assert!(names(variants[0]).is_empty());
+    assert_eq!(names(variants[1]), ["0", "1"]);
+    assert_eq!(names(variants[2]), ["c"]);
+
+    assert!(types(variants[0]).is_empty());
+    assert_eq!(types(variants[1]), ["u8", "Vec < u8 >"]);
+    assert_eq!(types(variants[2]), ["char"]);
+
+    let fields: Vec<_> = variants[1]
+        .fields_iter()
+        .map(|field| (field.attributes().len(), field.vis().is_some()))
+        .collect();
+    assert_eq!(fields, [(1, false), (0, true)]);
+}
+
+#[test]
+fn interpret_union_fields() {
+    let union_type = parse_declaration_checked(quote!(
+        union Hello {
+            #[attr]
+            a: u32,
+            pub b: f32,
+        }
+    ));
+    let union_type = union_type.as_union().unwrap();
+
+    let names: Vec<_> = union_type.field_names().collect();
+    assert_eq!(names, ["a", "b"]);
+
+    let types: Vec<_> = union_type
+        .field_types()
+        .map(|ty| ty.to_token_stream().to_string())
+        .collect();
+    assert_eq!(types, ["u32", "f32"]);
+
+    let fields: Vec<_> = union_type
+        .fields_iter()
+        .map(|field| (field.attributes().len(), field.vis().is_some()))
+        .collect();
+    assert_eq!(fields, [(1, false), (0, true)]);
+}
+
+#[test]
+fn interpret_ty_expr_phantom_data() {
+    let is_phantom_data = |tokens: TokenStream| {
+        let ty_expr = TyExpr {
+            tokens: tokens.into_iter().collect(),
+        };
+        ty_expr.is_phantom_data()
+    };
+
+    assert!(is_phantom_data(quote!(PhantomData<T>)));
+    assert!(is_phantom_data(quote!(PhantomData)));
+    assert!(is_phantom_data(quote!(std::marker::PhantomData<(A, B)>)));
+    assert!(is_phantom_data(quote!(::core::marker::PhantomData<&'a ()>)));
+
+    assert!(!is_phantom_data(quote!(Vec<T>)));
+    assert!(!is_phantom_data(quote!(my::marker::PhantomData<T>)));
+    assert!(!is_phantom_data(quote!(&PhantomData<T>)));
+    assert!(!is_phantom_data(quote!(PhantomDataWrapper<T>)));
+}
+
+#[test]
+fn interpret_referenced_idents() {
+    let struct_type = parse_struct_declaration(quote!(
+        struct Hello<'a, T>(
+            std::collections::HashMap<K, Box<dyn Iterator<Item = T> + 'a>>,
+            &'a mut Self,
+            <T as Trait>::Assoc,
+            fn(A) -> B,
+        );
+    ));
+
+    let idents: Vec<_> = struct_type
+        .referenced_idents()
+        .map(|ident| ident.to_string())
+        .collect();
+    assert_eq!(
+        idents,
+        ["HashMap", "K", "Box", "Iterator", "T", "Self", "T", "Trait", "Assoc", "A", "B"]
+    );
+}
+
+#[test]
+fn interpret_used_lifetimes() {
+    let used_lifetimes = |declaration: Declaration| -> Vec<_> {
+        declaration
+            .used_lifetimes()
+            .iter()
+            .map(|lifetime| lifetime.to_string())
+            .collect()
+    };
+
+    let enum_type = parse_declaration_checked(quote!(
+        enum Hello<'a, 'b: 'a>
+        where
+            for<'c> &'c T: Trait,
+        {
+            A(&'a str),
+            B { b: Cow<'b, [u8]>, c: &'static str },
+            C(Foo<'_>),
+        }
+    ));
+    let impl_decl = parse_declaration_checked(quote!(
+        impl<'x> Trait<'x> for Hello<'x, 'y> {
+            fn method<'m>(&self, m: &'m u8) -> &'m u8 {}
+        }
+    ));
+    let function = parse_declaration_checked(quote!(
+        fn f<'a, F>(x: &'a u8, g: F) -> &'b u8
+        where
+            F: for<'c> Fn(&'c u8) -> &'c u8,
+        {
+            'outer: loop {
+                break 'outer;
+            }
+        }
+    ));
+    let unit_struct = parse_declaration_checked(quote!(
+        struct Hello;
+    ));
+
+    assert_eq!(used_lifetimes(enum_type), ["a", "b"]);
+    assert_eq!(used_lifetimes(impl_decl), ["x", "y"]);
+    assert_eq!(used_lifetimes(function), ["a", "b"]);
+    assert!(used_lifetimes(unit_struct).is_empty());
+}
+
+#[test]
+fn walk_declaration_types() {
+    let types = |declaration: &Declaration| -> Vec<String> {
+        let mut types = Vec::new();
+        declaration.walk_types(|ty| types.push(ty.to_token_stream().to_string()));
+        types
+    };
+
+    let enum_type = parse_declaration_checked(quote!(
+        enum Hello<'a, T: Clone = u8, const N: usize = 4>
+        where
+            'a: 'static,
+            T: 'a,
+        {
+            A,
+            B(&'a T, [u8; N]),
+            C { c: Option<T> },
+        }
+    ));
+    assert_eq!(
+        types(&enum_type),
+        [
+            "Clone",
+            "u8",
+            "usize",
+            "T",
+            "& 'a T",
+            "[u8 ; N]",
+            "Option < T >"
+        ]
+    );
+
+    // Tuple structs place the where clause after the fields
+    let struct_type = parse_declaration_checked(quote!(
+        struct Hello<T, const N: usize = { 4 + 1 }>(T)
+        where
+            T: Clone + 'static;
+    ));
+    assert_eq!(types(&struct_type), ["usize", "T", "T", "Clone + 'static"]);
+
+    let impl_decl = parse_declaration_checked(quote!(
+        impl<T: Debug> Trait<T> for Hello<T> {
+            const C: usize = 0;
+            type Item = Vec<T>;
+            fn f(&self, a: A) -> B
+            where
+                B: Default,
+            {
+            }
+        }
+    ));
+    assert_eq!(
+        types(&impl_decl),
+        [
+            "Debug",
+            "Trait < T >",
+            "Hello < T >",
+            "usize",
+            "Vec < T >",
+            "A",
+            "B",
+            "B",
+            "Default"
+        ]
+    );
+
+    let mod_decl = parse_declaration_checked(quote!(
+        mod inner {
+            trait Foo: Bar {
+                fn g(x: X);
+            }
+            extern "C" {
+                static S: i32;
+            }
+            use std::fmt::Debug;
+        }
+    ));
+    assert_eq!(types(&mod_decl), ["Bar", "X", "i32"]);
+}
+
+#[test]
+fn map_declaration_types() {
+    let rename_foo = |declaration: &mut Declaration| {
+        declaration.map_types(|ty| {
+            let tokens = ty
+                .tokens
+                .iter()
+                .map(|token| match token {
+                    proc_macro2::TokenTree::Ident(ident) if ident == "Foo" => {
+                        quote!(Bar).into_iter().next().unwrap()
+                    }
+                    _ => token.clone(),
+                })
+                .collect();
+            ty.tokens = tokens;
+        });
+    };
+
+    let mut enum_type = parse_declaration_checked(quote!(
+        enum Hello<T: Foo = Foo, const N: usize = Foo>
+        where
+            Foo: Debug,
+        {
+            A(Foo),
+            B { b: Vec<Foo> },
+        }
+    ));
+    rename_foo(&mut enum_type);
+    assert_tokens_eq(
+        enum_type.to_token_stream(),
+        quote!(
+            enum Hello<T: Bar = Bar, const N: usize = Foo>
+            where
+                Bar: Debug,
+            {
+                A(Bar),
+                B { b: Vec<Bar> },
+            }
+        ),
+    );
+
+    let mut impl_decl = parse_declaration_checked(quote!(
+        impl<T> Foo for Wrapper<T>
+        where
+            T: Foo,
+        {
+            type Item = Foo;
+            fn f(&self, foo: Foo) -> Option<Foo> {
+                Foo::new()
+            }
+        }
+    ));
+    rename_foo(&mut impl_decl);
+    assert_tokens_eq(
+        impl_decl.to_token_stream(),
+        quote!(
+            impl<T> Bar for Wrapper<T>
+            where
+                T: Bar,
+            {
+                type Item = Bar;
+                fn f(&self, foo: Bar) -> Option<Bar> {
+                    Foo::new()
+                }
+            }
+        ),
+    );
+
+    // Visits the same positions as walk_types
+    let mut struct_type = parse_declaration_checked(quote!(
+        struct Hello<'a, T: Clone + 'a, const N: usize = 4>(&'a T, u8)
+        where
+            'a: 'static,
+            T: 'a,
+            T: Debug;
+    ));
+    let mut walked = Vec::new();
+    struct_type.walk_types(|ty| walked.push(ty.to_token_stream().to_string()));
+    let mut mapped = Vec::new();
+    struct_type.map_types(|ty| mapped.push(ty.to_token_stream().to_string()));
+    assert_eq!(walked, mapped);
+    assert_eq!(
+        mapped,
+        ["Clone + 'a", "usize", "& 'a T", "u8", "T", "T", "Debug"]
+    );
+}
+
+#[test]
+fn walk_declaration_attributes() {
+    let attribute_names = |declaration: &Declaration| -> Vec<String> {
+        let mut names = Vec::new();
+        declaration.walk_attributes(|attribute| {
+            names.push(attribute.get_single_path_segment().unwrap().to_string())
+        });
+        names
+    };
+
+    let struct_type = parse_declaration_checked(quote!(
+        #[derive(Clone)]
+        struct Hello(
+            #[a] u8,
+            #[b]
+            #[c]
+            u16,
+        );
+    ));
+    assert_eq!(attribute_names(&struct_type), ["derive", "a", "b", "c"]);
+
+    let mod_decl = parse_declaration_checked(quote!(
+        #[outer]
+        mod inner {
+            #![inner]
+
+            #[on_impl]
+            impl Foo {
+                #![in_impl]
+
+                #[on_method]
+                fn f(#[on_receiver] &self, #[on_param] x: u8) {}
+                #[on_const]
+                const C: u8 = 0;
+            }
+
+            enum E {
+                #[on_variant]
+                A {
+                    #[on_field]
+                    a: u8,
+                },
+            }
+        }
+    ));
+    assert_eq!(
+        attribute_names(&mod_decl),
+        [
+            "outer",
+            "inner",
+            "on_impl",
+            "in_impl",
+            "on_method",
+            "on_receiver",
+            "on_param",
+            "on_const",
+            "on_variant",
+            "on_field"
+        ]
+    );
+}
+
+#[test]
+fn interpret_ty_expr_reference() {
+    let ty_expr = |tokens: TokenStream| TyExpr {
+        tokens: tokens.into_iter().collect(),
+    };
+
+    let shared = ty_expr(quote!(&T));
+    let with_lifetime = ty_expr(quote!(&'a [u8]));
+    let mutable = ty_expr(quote!(&'a mut Vec<T>));
+    let nested = ty_expr(quote!(&&mut T));
+    let owned = ty_expr(quote!(Vec<&'a T>));
+
+    assert!(shared.is_reference());
+    assert!(!shared.is_mut_reference());
+    assert!(shared.reference_lifetime().is_none());
+    assert_eq!(
+        shared
+            .strip_reference()
+            .unwrap()
+            .to_token_stream()
+            .to_string(),
+        "T"
+    );
+
+    assert_eq!(with_lifetime.reference_lifetime().unwrap(), "a");
+    assert_eq!(
+        with_lifetime
+            .strip_reference()
+            .unwrap()
+            .to_token_stream()
+            .to_string(),
+        "[u8]"
+    );
+
+    assert!(mutable.is_mut_reference());
+    assert_eq!(mutable.reference_lifetime().unwrap(), "a");
+    assert_eq!(
+        mutable
+            .strip_reference()
+            .unwrap()
+            .to_token_stream()
+            .to_string(),
+        "Vec < T >"
+    );
+
+    assert!(!nested.is_mut_reference());
+    assert!(nested.strip_reference().unwrap().is_mut_reference());
+
+    assert!(!owned.is_reference());
+    assert!(owned.strip_reference().is_none());
+}
+
+#[test]
+fn interpret_ty_expr_trait_types() {
+    let dyn_type = TyExpr::from_tokens(quote!(dyn Display + Send));
+    let impl_type = TyExpr::from_tokens(quote!(impl Iterator<Item = u8>));
+    let dyn_ref = TyExpr::from_tokens(quote!(&'a dyn Error));
+    let boxed = TyExpr::from_tokens(quote!(Box<dyn Error>));
+
+    assert!(dyn_type.is_dyn());
+    assert!(!dyn_type.is_impl_trait());
+    assert_tokens_eq(dyn_type.trait_bounds().unwrap(), quote!(Display + Send));
+
+    assert!(impl_type.is_impl_trait());
+    assert!(!impl_type.is_dyn());
+    assert_tokens_eq(
+        impl_type.trait_bounds().unwrap(),
+        quote!(Iterator<Item = u8>),
+    );
+
+    assert!(!dyn_ref.is_dyn());
+    let referent = dyn_ref.strip_reference().unwrap();
+    assert!(referent.is_dyn());
+    assert_tokens_eq(referent.trait_bounds().unwrap(), quote!(Error));
+
+    assert!(!boxed.is_dyn());
+    assert!(!boxed.is_impl_trait());
+    assert!(boxed.trait_bounds().is_none());
+}
+
+#[test]
+fn interpret_ty_expr_tuple_and_array() {
+    let elements = |ty: TokenStream| -> Option<Vec<String>> {
+        let elements = TyExpr::from_tokens(ty).as_tuple()?;
+        Some(
+            elements
+                .iter()
+                .map(|element| element.to_token_stream().to_string())
+                .collect(),
+        )
+    };
+
+    assert_eq!(
+        elements(quote!((A, HashMap<K, V>, fn(u8, u8) -> Vec<u8>))).unwrap(),
+        ["A", "HashMap < K , V >", "fn (u8 , u8) -> Vec < u8 >"]
+    );
+    assert_eq!(elements(quote!((A,))).unwrap(), ["A"]);
+    assert!(elements(quote!(())).unwrap().is_empty());
+    assert!(elements(quote!((A))).is_none());
+    assert!(elements(quote!([A; 2])).is_none());
+    assert!(elements(quote!((A>, B))).is_none());
+    assert!(elements(quote!((A,,))).is_none());
+
+    let (element, len) = TyExpr::from_tokens(quote!([[u8; 4]; N + 1]))
+        .as_array()
+        .unwrap();
+    assert_tokens_eq(len, quote!(N + 1));
+    let (inner, inner_len) = element.as_array().unwrap();
+    assert_tokens_eq(inner.to_token_stream(), quote!(u8));
+    assert_tokens_eq(inner_len, quote!(4));
+
+    assert!(TyExpr::from_tokens(quote!([u8])).as_array().is_none());
+    assert!(TyExpr::from_tokens(quote!((u8, u8))).as_array().is_none());
+}
+
+// ================
+// MOD DECLARATIONS
+// ================
+
+#[test]
+fn parse_mod() {
+    let expr = quote! {
+        #[path = "some/module"]
+        mod one_module {
+            #![allow(clippy::iter_with_drain)]
+
+            use std::clone::Clone as Clown;
+            use std::cell::{Cell, RefCell};
+
+            pub struct MyStruct {
+                field: i32,
+            }
+            impl MyStruct {}
+            impl MyTrait for MyStruct {}
+
+            #[derive(Copy, Clone)]
+            enum Enum {
+                Variant,
+            }
+
+            fn f() -> bool { true }
+
+            pub use crate::{A, self, b::c};
+
+            const C: i32 = -8 * 2;
+
+            pub static MUTEX: std::sync::Mutex<i32> = std::sync::Mutex::new(0);
+
+            type MyType = Rc<RefCell<MyStruct>>;
+
+            mod nested_mod {
+                fn g() {}
+            }
+
+            mod empty_mod {}
+            pub(crate) mod foreign_mod;
+
+            #[contain_it]
+            unsafe mod hazard_mod {}
+        }
+    };
+
+    let mod_decl = parse_declaration_checked(expr);
+    assert_debug_snapshot!(mod_decl);
+}
+
+#[test]
+fn parse_mod_inner_and_outer_attributes() {
+    let mod_decl = parse_declaration_checked(quote! {
+        #[outer]
+        mod one_module {
+            #![inner]
+            #![inner2]
+
+            #[member]
+            struct Member;
+        }
+    });
+    let mod_decl = match &mod_decl {
+        Declaration::Module(mod_decl) => mod_decl,
+        _ => unreachable!(),
+    };
+    let names = |attributes: &[Attribute]| -> Vec<_> {
+        attributes
+            .iter()
+            .map(|attribute| attribute.get_single_path_segment().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(names(&mod_decl.attributes), ["outer"]);
+    assert!(mod_decl.attributes.iter().all(Attribute::is_outer));
+
+    assert_eq!(names(&mod_decl.inner_attributes), ["inner", "inner2"]);
+    assert!(mod_decl.inner_attributes.iter().all(Attribute::is_inner));
+
+    let member_attributes = mod_decl.members[0].attributes();
+    assert_eq!(names(member_attributes), ["member"]);
+    assert!(member_attributes[0].is_outer());
+}
+
+#[test]
+fn interpret_nested_mods() {
+    fn collect_names(declarations: &[Declaration], names: &mut Vec<String>) {
+        for declaration in declarations {
+            names.push(declaration.name().unwrap().to_string());
+            if let Declaration::Module(mod_decl) = declaration {
+                collect_names(&mod_decl.members, names);
+            }
+        }
+    }
+
+    let declarations = parse_declarations(quote! {
+        mod outer {
+            struct A;
+            mod inner {
+                fn b() {}
+            }
+            mod external;
+        }
+        struct C;
+    })
+    .unwrap();
+
+    let mut names = vec![];
+    collect_names(&declarations, &mut names);
+    assert_eq!(names, ["outer", "A", "inner", "b", "external", "C"]);
+
+    let external = match &declarations[0] {
+        Declaration::Module(outer) => match &outer.members[2] {
+            Declaration::Module(external) => external,
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    assert!(external.tk_semicolon.is_some());
+    assert!(external.tk_braces.is_none());
+    assert!(external.members.is_empty());
+}
+
+#[test]
+fn interpret_use_declarations() {
+    let declarations = parse_declarations(quote! {
+        use std::collections::HashMap;
+        #[cfg(test)]
+        pub(crate) use ::serde::{Serialize, Deserialize};
+        use self::inner::*;
+        use {a, b};
+    })
+    .unwrap();
+    let uses: Vec<_> = declarations
+        .iter()
+        .map(|declaration| declaration.as_use().unwrap())
+        .collect();
+
+    let leading: Vec<_> = uses
+        .iter()
+        .map(|use_decl| use_decl.leading_segment().map(|ident| ident.to_string()))
+        .collect();
+    assert_eq!(
+        leading,
+        [
+            Some("std".to_string()),
+            Some("serde".to_string()),
+            Some("self".to_string()),
+            None
+        ]
+    );
+
+    assert!(uses[0].vis_marker.is_none());
+    assert!(uses[1].vis_marker.is_some());
+    assert_eq!(uses[1].attributes.len(), 1);
+    assert_tokens_eq(
+        uses[1].import_tree.to_token_stream(),
+        quote!(::serde::{Serialize, Deserialize}),
+    );
+}
+
+#[test]
+fn parse_multiple_declarations() {
+    let expr = quote! {
+        #[derive(Debug)]
+        pub struct MyStruct {
+            field: i32,
+        }
+
+        impl MyStruct {}
+
+        fn f() -> MyStruct {}
+
+        use std::rc::Rc;
+    };
+
+    let declarations = parse_declarations(expr.clone()).unwrap();
+    let names: Vec<_> = declarations
+        .iter()
+        .map(|declaration| declaration.name().map(|name| name.to_string()))
+        .collect();
+
+    assert_eq!(
+        names,
+        [
+            Some("MyStruct".to_string()),
+            None,
+            Some("f".to_string()),
+            None
+        ]
+    );
+    similar_asserts::assert_str_eq!(quote!(#(#declarations)*), expr);
+    assert!(parse_declarations(quote!()).unwrap().is_empty());
+
+    let kinds: Vec<_> = declarations.iter().map(Declaration::kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            DeclKind::Struct,
+            DeclKind::Impl,
+            DeclKind::Function,
+            DeclKind::Use
+        ]
+    );
+}
+
+// ==================
+// TRAIT DECLARATIONS
 // ==================
 
 #[test]
@@ -1315,6 +3700,116 @@ fn parse_trait_simple() {
     assert_debug_snapshot!(trait_decl);
 }
 
+#[test]
+fn parse_trait_generic_assoc_types() {
+    let trait_decl = parse_declaration_checked(quote! {
+        trait LendingIterator {
+            type Item<'a>: Iterator<Item = &'a u8> + 'a where Self: 'a;
+            type Wrapper<T: Clone>: From<T> = Vec<T>;
+        }
+    });
+
+    let assoc_types: Vec<_> = trait_decl
+        .as_trait()
+        .unwrap()
+        .body_items
+        .iter()
+        .map(|member| match member {
+            TraitMember::AssocTy(assoc_ty) => assoc_ty,
+            _ => unreachable!(),
+        })
+        .collect();
+
+    let item = assoc_types[0];
+    assert_eq!(item.name, "Item");
+    assert_eq!(item.generic_params.as_ref().unwrap().params.len(), 1);
+    assert_eq!(
+        item.bound.as_ref().unwrap().to_token_stream().to_string(),
+        ": Iterator < Item = & 'a u8 > + 'a"
+    );
+    assert!(item.where_clause.is_some());
+    assert!(item.initializer_ty.is_none());
+
+    let wrapper = assoc_types[1];
+    assert_eq!(wrapper.name, "Wrapper");
+    assert!(wrapper.generic_params.as_ref().unwrap().params[0]
+        .0
+        .bound
+        .is_some());
+    assert_eq!(
+        wrapper
+            .initializer_ty
+            .as_ref()
+            .unwrap()
+            .to_token_stream()
+            .to_string(),
+        "Vec < T >"
+    );
+
+    // Impls place the where clause after the type
+    let impl_decl = parse_declaration_checked(quote! {
+        impl LendingIterator for Foo {
+            type Item<'a> = Iter<'a> where Self: 'a;
+        }
+    });
+    let impl_item = match &impl_decl.as_impl().unwrap().body_items[..] {
+        [ImplMember::AssocTy(assoc_ty)] => assoc_ty,
+        items => panic!("expected a single associated type, got {:?}", items),
+    };
+    assert!(impl_item.where_clause.is_none());
+    assert_tokens_eq(impl_item.initializer_ty.to_token_stream(), quote!(Iter<'a>));
+    let where_items: Vec<_> = impl_item
+        .trailing_where_clause
+        .as_ref()
+        .unwrap()
+        .items()
+        .map(|item| item.to_token_stream().to_string())
+        .collect();
+    assert_eq!(where_items, ["Self : 'a"]);
+}
+
+#[test]
+fn interpret_assoc_consts() {
+    let trait_decl = parse_declaration_checked(quote! {
+        trait MyTrait {
+            const MAX: usize;
+            #[doc = "Minimum"]
+            const MIN: usize = 0;
+            type Item;
+            fn f();
+        }
+    });
+    let impl_decl = parse_declaration_checked(quote! {
+        impl MyTrait for MyType {
+            const MAX: usize = 10;
+            type Item = u8;
+        }
+    });
+
+    let trait_consts: Vec<_> = trait_decl.as_trait().unwrap().assoc_consts().collect();
+    assert_eq!(trait_consts.len(), 2);
+    assert_eq!(trait_consts[0].name, "MAX");
+    assert_eq!(trait_consts[0].ty.to_token_stream().to_string(), "usize");
+    assert!(!trait_consts[0].has_value());
+    assert!(trait_consts[0].tk_equals.is_none());
+    assert_eq!(trait_consts[1].name, "MIN");
+    assert!(trait_consts[1].has_value());
+    assert_eq!(
+        trait_consts[1]
+            .initializer
+            .as_ref()
+            .unwrap()
+            .to_token_stream()
+            .to_string(),
+        "0"
+    );
+
+    let impl_consts: Vec<_> = impl_decl.as_impl().unwrap().assoc_consts().collect();
+    assert_eq!(impl_consts.len(), 1);
+    assert_eq!(impl_consts[0].name, "MAX");
+    assert!(impl_consts[0].has_value());
+}
+
 #[test]
 fn parse_trait_decorated() {
     let expr = quote! {
@@ -1327,3 +3822,322 @@ fn parse_trait_decorated() {
     let trait_decl = parse_declaration_checked(expr);
     assert_debug_snapshot!(trait_decl);
 }
+
+#[test]
+fn parse_trait_supertraits() {
+    let expr = quote! {
+        pub trait MyTrait<T>: Bar + Baz<T> + 'static
+        where
+            T: Clone,
+        {
+            fn get(&self) -> T;
+        }
+    };
+
+    let trait_decl = parse_declaration_checked(expr);
+    assert_eq!(trait_decl.name().unwrap().to_string(), "MyTrait");
+    assert_eq!(trait_decl.generic_params().unwrap().params.len(), 1);
+
+    let trait_decl = trait_decl.as_trait().unwrap();
+    assert_quote_snapshot!(trait_decl.bound);
+    assert_eq!(trait_decl.body_items.len(), 1);
+}
+
+#[test]
+fn interpret_trait_method_bodies() {
+    let trait_decl = parse_declaration_checked(quote! {
+        trait MyTrait {
+            fn required(&self) -> i32;
+            fn provided(&self) -> i32 { 42 }
+        }
+    });
+
+    let methods: Vec<_> = trait_decl
+        .as_trait()
+        .unwrap()
+        .body_items
+        .iter()
+        .map(|member| match member {
+            TraitMember::Method(method) => (method.name.to_string(), method.has_body()),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    assert_eq!(
+        methods,
+        [
+            ("required".to_string(), false),
+            ("provided".to_string(), true)
+        ]
+    );
+}
+
+#[test]
+fn parse_trait_async_methods() {
+    let trait_decl = parse_declaration_checked(quote! {
+        trait MyTrait {
+            async fn required(&self) -> i32;
+            async unsafe fn required_unsafe(&mut self);
+            async fn provided(&self) -> i32 { self.required().await }
+            fn sync_provided(&self) {}
+        }
+    });
+
+    let methods: Vec<_> = trait_decl
+        .as_trait()
+        .unwrap()
+        .body_items
+        .iter()
+        .map(|member| match member {
+            TraitMember::Method(method) => (method.is_async(), method.has_body()),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    assert_eq!(
+        methods,
+        [(true, false), (true, false), (true, true), (false, true)]
+    );
+}
+
+#[test]
+fn interpret_unsafe_trait_and_impl() {
+    let unsafe_trait = parse_declaration_checked(quote!(
+        pub unsafe trait MyTrait {}
+    ));
+    let safe_trait = parse_declaration_checked(quote!(
+        trait MyTrait {}
+    ));
+    let unsafe_impl = parse_declaration_checked(quote!(
+        unsafe impl MyTrait for MyStruct {}
+    ));
+    let safe_impl = parse_declaration_checked(quote!(
+        impl MyTrait for MyStruct {}
+    ));
+
+    assert!(unsafe_trait.as_trait().unwrap().is_unsafe());
+    assert!(!safe_trait.as_trait().unwrap().is_unsafe());
+    assert!(unsafe_impl.as_impl().unwrap().is_unsafe());
+    assert!(!safe_impl.as_impl().unwrap().is_unsafe());
+
+    // `unsafe` is preserved when forwarding the declaration
+    assert_eq!(
+        unsafe_trait.to_token_stream().to_string(),
+        "pub unsafe trait MyTrait { }"
+    );
+}
+
+// =================
+// MACRO DEFINITIONS
+// =================
+
+#[test]
+fn parse_macro_def() {
+    let expr = quote!(
+        #[macro_export]
+        macro_rules! my_macro {
+            () => {};
+            ($x:expr, $($rest:tt)*) => { $x + my_macro!($($rest)*) };
+        }
+    );
+
+    let macro_decl = parse_declaration_checked(expr);
+    assert_debug_snapshot!(macro_decl);
+}
+
+#[test]
+fn parse_macro_def_delimiters() {
+    let paren_macro = parse_declaration_checked(quote!(
+        macro_rules! paren_macro (() => {});
+    ));
+    let paren_macro = paren_macro.as_macro_def().unwrap();
+    assert_eq!(paren_macro.name.to_string(), "paren_macro");
+    assert!(paren_macro.tk_semicolon.is_some());
+    assert_quote_snapshot!(paren_macro.rules);
+
+    let declarations = parse_declarations(quote!(
+        macro_rules! first {
+            () => {};
+        }
+        macro_rules! second [() => {}];
+        struct Foo;
+    ))
+    .unwrap();
+    let names: Vec<_> = declarations
+        .iter()
+        .map(|declaration| declaration.name().unwrap().to_string())
+        .collect();
+    assert_eq!(names, ["first", "second", "Foo"]);
+}
+
+// ======
+// ERRORS
+// ======
+
+#[test]
+fn parse_unknown_declaration() {
+    let error = parse_declaration(quote!(
+        let x = 42;
+    ))
+    .unwrap_err();
+
+    assert!(error.to_string().starts_with("cannot parse declaration"));
+}
+
+#[test]
+fn parse_empty_declaration() {
+    let error = parse_declaration(quote!(#[attribute] pub)).unwrap_err();
+
+    assert!(error.to_string().contains("found end-of-stream"));
+}
+
+#[test]
+fn parse_struct_missing_body() {
+    assert!(parse_declaration(quote!(struct Hello)).is_err());
+    assert!(parse_declaration(quote!(struct Hello [A, B])).is_err());
+}
+
+#[test]
+fn parse_enum_missing_body() {
+    assert!(parse_declaration(quote!(enum Hello)).is_err());
+    assert!(parse_declaration(quote!(enum Hello(A, B);)).is_err());
+}
+
+#[test]
+fn parse_dangling_attributes() {
+    let named_error = parse_declaration_str(
+        "
+        struct Hello {
+            a: A,
+            /// Dangling
+        }
+        ",
+    )
+    .unwrap_err();
+    let tuple_error = parse_declaration_str("struct Hello(A, #[attr]);").unwrap_err();
+    let variant_error = parse_declaration_str(
+        "
+        enum Hello {
+            A,
+            /// Dangling
+        }
+        ",
+    )
+    .unwrap_err();
+    let variant_field_error = parse_declaration_str("enum Hello { A { #[attr] } }").unwrap_err();
+
+    assert!(named_error
+        .to_string()
+        .starts_with("cannot parse named fields: found an attribute with no item"));
+    assert!(tuple_error
+        .to_string()
+        .starts_with("cannot parse tuple fields"));
+    assert!(variant_error
+        .to_string()
+        .starts_with("cannot parse enum variants"));
+    assert!(variant_field_error
+        .to_string()
+        .starts_with("cannot parse named fields"));
+}
+
+#[test]
+fn parse_macro_def_with_vis() {
+    let error = parse_declaration(quote!(
+        pub macro_rules! my_macro {}
+    ))
+    .unwrap_err();
+
+    assert!(error.to_string().contains("cannot have a visibility"));
+}
+
+#[test]
+fn parse_from_str_lex_error() {
+    let error = parse_declaration_str("struct Hello { a: A").unwrap_err();
+
+    assert!(error.to_string().starts_with("cannot lex declaration"));
+}
+
+#[test]
+fn parse_declarations_unknown_item() {
+    let error = parse_declarations(quote!(
+        struct Hello;
+        let x = 42;
+    ))
+    .unwrap_err();
+
+    assert!(error.to_string().starts_with("cannot parse declaration"));
+}
+
+#[test]
+fn parse_invalid_where_clause() {
+    let error = parse_declaration(quote!(
+        struct S<T> where T Clone;
+    ))
+    .unwrap_err();
+
+    assert!(error.to_string().starts_with("cannot parse where clause"));
+}
+
+#[test]
+fn parse_invalid_nested_items() {
+    let errors = [
+        parse_declaration(quote!(struct S<T Clone>(T);)),
+        parse_declaration(quote!(struct S { a })),
+        parse_declaration(quote!(struct S(A,,);)),
+        parse_declaration(quote!(enum E { A = })),
+        parse_declaration(quote!(fn f(a) {})),
+        parse_declaration(quote!(fn f() -> u8)),
+        parse_declaration(quote!(const mut A: u8 = 1;)),
+        parse_declaration(quote!(impl Foo { struct Bar; })),
+        parse_declaration(quote!(trait Foo)),
+        parse_declaration(quote!(mod foo { let x = 1; })),
+        parse_declaration(quote!(use foo)),
+        parse_declaration(quote!(#[attr] #![inner] struct S;)),
+    ];
+
+    for error in errors {
+        assert!(error.unwrap_err().to_string().starts_with("cannot parse"));
+    }
+}
+
+#[test]
+fn parse_error_span() {
+    let tokens: TokenStream = "struct S { a A }".parse().unwrap();
+    let field_type = match tokens.clone().into_iter().nth(2) {
+        Some(proc_macro2::TokenTree::Group(group)) => group.stream().into_iter().nth(1).unwrap(),
+        _ => unreachable!(),
+    };
+    let error = parse_declaration(tokens).unwrap_err();
+
+    assert_eq!(
+        format!("{:?}", error.span()),
+        format!("{:?}", field_type.span())
+    );
+}
+
+// =====
+// SERDE
+// =====
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let declaration = parse_declaration_checked(quote!(
+        #[derive(Debug)]
+        pub struct Hello<T: Clone> {
+            a: Vec<T>,
+        }
+    ));
+
+    let json = serde_json::to_string(&declaration).unwrap();
+    let deserialized: Declaration = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, declaration);
+
+    let struct_type: Struct = serde_json::from_str(&json).unwrap();
+    assert_eq!(Declaration::Struct(struct_type), declaration);
+
+    let ty_expr: TyExpr = serde_json::from_str("\"Vec < T >\"").unwrap();
+    assert_eq!(serde_json::to_string(&ty_expr).unwrap(), "\"Vec < T >\"");
+
+    assert!(serde_json::from_str::<crate::Enum>(&json).is_err());
+}
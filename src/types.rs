@@ -33,10 +33,32 @@ pub enum Declaration {
     Module(Module),
     Trait(Trait),
     Impl(Impl),
+    ExternBlock(ExternBlock),
     TyDefinition(TyDefinition),
     Function(Function),
     Constant(Constant),
     Use(UseDeclaration),
+    MacroDef(MacroDef),
+}
+
+/// The kind of a [`Declaration`], without its contents.
+///
+/// Returned by [`Declaration::kind`].
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeclKind {
+    Struct,
+    Enum,
+    Union,
+    Module,
+    Trait,
+    Impl,
+    ExternBlock,
+    TyDefinition,
+    Function,
+    Constant,
+    Use,
+    MacroDef,
 }
 
 /// Declaration of a struct.
@@ -249,6 +271,37 @@ pub enum ImplMember {
     // other items like macro!{...} or macro!(...); invocations
 }
 
+/// Declaration of an `extern` block of foreign items.
+///
+/// **Example input:**
+///
+/// ```no_run
+/// extern "C" {
+///     fn abs(x: i32) -> i32;
+///     static errno: i32;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExternBlock {
+    pub attributes: Vec<Attribute>,
+    pub tk_unsafe: Option<Ident>,
+    pub tk_extern: Ident,
+    pub extern_abi: Option<Literal>,
+    pub tk_braces: GroupSpan,
+    pub inner_attributes: Vec<Attribute>,
+    pub body_items: Vec<ExternMember>,
+}
+
+/// An item inside an `extern` block.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum ExternMember {
+    Function(Function),
+    Static(Constant),
+    Ty(TyDefinition),
+    // other items like macro!{...} or macro!(...); invocations
+}
+
 /// Constant or static declaration.
 ///
 /// **Example inputs:**
@@ -286,6 +339,7 @@ pub struct Constant {
 ///
 /// ```no_run
 /// type MyType = i32;
+/// type MyVec<T> = Vec<T>;
 /// ```
 #[derive(Clone, Debug)]
 pub struct TyDefinition {
@@ -293,9 +347,14 @@ pub struct TyDefinition {
     pub vis_marker: Option<VisMarker>,
     pub tk_type: Ident,
     pub name: Ident,
+    pub generic_params: Option<GenericParamList>,
     pub bound: Option<GenericBound>,
+    /// Where clause before the `=`, eg in `type Item<'a> where Self: 'a;`
+    pub where_clause: Option<WhereClause>,
     pub tk_equals: Option<Punct>,
     pub initializer_ty: Option<TyExpr>,
+    /// Where clause after the initializer, eg in `type Item<'a> = Iter<'a> where Self: 'a;`
+    pub trailing_where_clause: Option<WhereClause>,
     pub tk_semicolon: Punct,
 }
 
@@ -309,6 +368,10 @@ pub struct TyDefinition {
 /// # #[cfg(FALSE)]
 /// fn do_thing<T: Clone>(t: T) where T: Default;
 /// ```
+///
+/// `attributes` holds the outer attributes, in source order. Inner attributes
+/// such as `#![allow(unused)]` at the start of the body are not parsed, and stay
+/// in the `body` tokens. Attributes on parameters are stored in each [`FnParam`].
 #[derive(Clone, Debug)]
 pub struct Function {
     pub attributes: Vec<Attribute>,
@@ -361,7 +424,7 @@ pub enum FnParam {
 /// Possible parameters captures by this are `self`, `mut self`, `&self` or `&mut self`.
 /// Reference lifetimes are not yet supported.
 ///
-/// Parameters of the form `self: Pin<&mut Self>` are recognized as [`FunctionTypedParameter`].
+/// Parameters of the form `self: Pin<&mut Self>` are recognized as [`FnTypedParam`].
 #[derive(Clone, Debug)]
 pub struct FnReceiverParam {
     pub attributes: Vec<Attribute>,
@@ -416,6 +479,15 @@ pub struct NamedField {
     pub ty: TyExpr,
 }
 
+/// A borrowed field of a [`Struct`], either named or tuple.
+///
+/// Returned by [`Struct::fields_iter`], to handle both kinds of fields uniformly.
+#[derive(Clone, Copy, Debug)]
+pub enum Field<'a> {
+    Named(&'a NamedField),
+    Tuple(&'a TupleField),
+}
+
 // --- Token groups ---
 
 /// An outer or inner attribute.
@@ -456,6 +528,28 @@ pub enum AttributeValue {
     Empty,
 }
 
+/// A single hint of a `#[repr(...)]` attribute.
+///
+/// For instance, `#[repr(C, u8)]` has the hints `C` and `Int(u8)`.
+///
+/// See also: https://doc.rust-lang.org/reference/type-layout.html#representations
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum Repr {
+    /// `#[repr(C)]`
+    C,
+    /// `#[repr(transparent)]`
+    Transparent,
+    /// Primitive representation, eg `#[repr(u8)]` or `#[repr(isize)]`.
+    Int(Ident),
+    /// `#[repr(packed)]` or `#[repr(packed(N))]`
+    Packed(Option<Literal>),
+    /// `#[repr(align(N))]`
+    Align(Literal),
+    /// Any hint venial doesn't recognize.
+    Other(Vec<TokenTree>),
+}
+
 /// Visibility marker, eg `pub`, `pub(crate)`, `pub(super)`, etc.
 #[derive(Clone)]
 pub struct VisMarker {
@@ -485,15 +579,33 @@ pub struct GenericParamList {
 ///
 /// ```no_run
 /// # struct MyUnitStruct<
-/// 'a, B, const C: usize,
-/// # >(&'a [B; C]);
+/// 'a, B, const C: usize, D = i32, const E: usize = 8,
+/// # >(&'a [B; C], D);
 /// ```
 #[derive(Clone)]
 pub struct GenericParam {
+    /// Attributes in front of the param, as in `#[may_dangle] T`.
+    pub attributes: Vec<Attribute>,
     /// Either `'` for lifetimes, `const` for const parameters, or None for type parameters.
     pub tk_prefix: Option<TokenTree>,
     pub name: Ident,
     pub bound: Option<GenericBound>,
+    pub tk_equals: Option<Punct>,
+    /// Default type or value of the param, as in `T = i32` or `const N: usize = 8`.
+    pub default_value: Option<TyExpr>,
+}
+
+/// The kind of a [`GenericParam`].
+///
+/// Returned by [`GenericParam::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GenericParamKind {
+    /// `'a`
+    Lifetime,
+    /// `T`
+    Type,
+    /// `const N: usize`
+    Const,
 }
 
 /// A parameter bound in a type's generic list.
@@ -560,6 +672,31 @@ pub enum GenericArg {
 /// differently when passed to quote macros.
 pub struct InlineGenericArgs<'a>(pub(crate) &'a GenericParamList);
 
+/// Generic params to be quoted after the `impl` keyword, returned by
+/// [`HasGenerics::split_for_impl`](crate::HasGenerics::split_for_impl).
+///
+/// For instance, `<'a: 'static, #[my_attr] T: Clone = i32>` becomes `<'a: 'static, T: Clone,>`.
+/// Quotes to nothing if the declaration has no generic params.
+pub struct ImplGenerics<'a>(pub(crate) Option<&'a GenericParamList>);
+
+/// Generic args to be quoted after the type name, returned by
+/// [`HasGenerics::split_for_impl`](crate::HasGenerics::split_for_impl).
+///
+/// Same as [`InlineGenericArgs`], except it quotes to nothing if the declaration
+/// has no generic params.
+pub struct TypeGenerics<'a>(pub(crate) Option<&'a GenericParamList>);
+
+/// Generic args of a [`Function`] in turbofish form, returned by
+/// [`Function::get_turbofish_generic_args`].
+///
+/// For instance, `<'a, T: Clone, const N: usize>` becomes `::<'a, T, N>`, or
+/// `::<T, N>` if lifetimes are excluded. Quotes to nothing if there are no
+/// args left to forward.
+pub struct TurbofishGenericArgs<'a> {
+    pub(crate) generic_params: Option<&'a GenericParamList>,
+    pub(crate) include_lifetimes: bool,
+}
+
 /// All the stuff that comes after the `where` keyword.
 #[derive(Clone)]
 pub struct WhereClause {
@@ -648,6 +785,32 @@ pub struct UseDeclaration {
     pub tk_semicolon: Punct,
 }
 
+/// Declarative macro definition.
+///
+/// The rules are not parsed, and kept as raw tokens.
+///
+/// **Example input:**
+///
+/// ```no_run
+/// macro_rules! my_macro {
+///     ($x:expr) => { $x + 1 };
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MacroDef {
+    pub attributes: Vec<Attribute>,
+    /// The `macro_rules` keyword
+    pub tk_macro_rules: Ident,
+    pub tk_bang: Punct,
+    pub name: Ident,
+    /// Delimiters around the rules, usually `{ }`
+    pub tk_rules_group: GroupSpan,
+    /// Un-tokenized rules inside the delimiters
+    pub rules: TokenStream,
+    /// Semicolon, required after `( )` and `[ ]` delimiters
+    pub tk_semicolon: Option<Punct>,
+}
+
 /// The value of an [`EnumVariant`], normally for c-like enums.
 ///
 /// **Example input:**
@@ -820,11 +983,17 @@ impl std::fmt::Debug for GenericParamList {
 impl std::fmt::Debug for GenericParam {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut f = f.debug_struct("GenericParam");
+        if !self.attributes.is_empty() {
+            f.field("attributes", &self.attributes);
+        }
         if let Some(prefix) = self.tk_prefix.as_ref() {
             f.field("tk_prefix", &prefix.to_string());
         }
         f.field("name", &self.name.to_string());
         f.field("bound", &self.bound);
+        if let Some(default_value) = self.default_value.as_ref() {
+            f.field("default_value", default_value);
+        }
         f.finish()
     }
 }
@@ -874,6 +1043,23 @@ impl std::fmt::Debug for ValueExpr {
     }
 }
 
+impl std::fmt::Debug for MacroDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rules = ValueExpr {
+            tokens: self.rules.clone().into_iter().collect(),
+        };
+        f.debug_struct("MacroDef")
+            .field("attributes", &self.attributes)
+            .field("tk_macro_rules", &self.tk_macro_rules)
+            .field("tk_bang", &self.tk_bang)
+            .field("name", &self.name)
+            .field("tk_rules_group", &self.tk_rules_group)
+            .field("rules", &rules)
+            .field("tk_semicolon", &self.tk_semicolon)
+            .finish()
+    }
+}
+
 impl std::fmt::Debug for GroupSpan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.delimiter {
@@ -891,9 +1077,7 @@ impl GroupSpan {
     fn quote_with(&self, tokens: &mut TokenStream, f: impl FnOnce(&mut TokenStream)) {
         let mut inner = TokenStream::new();
         f(&mut inner);
-        let mut g = Group::new(self.delimiter, inner);
-        g.set_span(self.span);
-        tokens.append(g);
+        tokens.append(self.clone().into_group(inner));
     }
 }
 
@@ -906,10 +1090,12 @@ impl ToTokens for Declaration {
             Declaration::Module(mod_decl) => mod_decl.to_tokens(tokens),
             Declaration::Trait(trait_decl) => trait_decl.to_tokens(tokens),
             Declaration::Impl(impl_decl) => impl_decl.to_tokens(tokens),
+            Declaration::ExternBlock(extern_decl) => extern_decl.to_tokens(tokens),
             Declaration::TyDefinition(ty_decl) => ty_decl.to_tokens(tokens),
             Declaration::Function(function_decl) => function_decl.to_tokens(tokens),
             Declaration::Constant(const_decl) => const_decl.to_tokens(tokens),
             Declaration::Use(use_decl) => use_decl.to_tokens(tokens),
+            Declaration::MacroDef(macro_decl) => macro_decl.to_tokens(tokens),
         }
     }
 }
@@ -1014,9 +1200,12 @@ impl ToTokens for TyDefinition {
         self.vis_marker.to_tokens(tokens);
         self.tk_type.to_tokens(tokens);
         self.name.to_tokens(tokens);
+        self.generic_params.to_tokens(tokens);
         self.bound.to_tokens(tokens);
+        self.where_clause.to_tokens(tokens);
         self.tk_equals.to_tokens(tokens);
         self.initializer_ty.to_tokens(tokens);
+        self.trailing_where_clause.to_tokens(tokens);
         self.tk_semicolon.to_tokens(tokens);
     }
 }
@@ -1118,6 +1307,36 @@ impl ToTokens for ImplMember {
     }
 }
 
+impl ToTokens for ExternBlock {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for attribute in &self.attributes {
+            attribute.to_tokens(tokens);
+        }
+        self.tk_unsafe.to_tokens(tokens);
+        self.tk_extern.to_tokens(tokens);
+        self.extern_abi.to_tokens(tokens);
+        self.tk_braces.quote_with(tokens, |tokens| {
+            for attribute in &self.inner_attributes {
+                attribute.to_tokens(tokens);
+            }
+
+            for item in self.body_items.iter() {
+                item.to_tokens(tokens)
+            }
+        });
+    }
+}
+
+impl ToTokens for ExternMember {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            ExternMember::Function(function) => function.to_tokens(tokens),
+            ExternMember::Static(static_decl) => static_decl.to_tokens(tokens),
+            ExternMember::Ty(ty_decl) => ty_decl.to_tokens(tokens),
+        }
+    }
+}
+
 impl ToTokens for TraitMember {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
@@ -1298,9 +1517,14 @@ impl ToTokens for GenericParamList {
 
 impl ToTokens for GenericParam {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        for attribute in &self.attributes {
+            attribute.to_tokens(tokens);
+        }
         self.tk_prefix.to_tokens(tokens);
         self.name.to_tokens(tokens);
         self.bound.to_tokens(tokens);
+        self.tk_equals.to_tokens(tokens);
+        self.default_value.to_tokens(tokens);
     }
 }
 
@@ -1364,6 +1588,61 @@ impl ToTokens for InlineGenericArgs<'_> {
     }
 }
 
+impl ToTokens for TurbofishGenericArgs<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let generic_params = match self.generic_params {
+            Some(generic_params) => generic_params,
+            None => return,
+        };
+        let mut params = generic_params
+            .params
+            .items()
+            .filter(|param| self.include_lifetimes || !param.is_lifetime())
+            .peekable();
+        if params.peek().is_none() {
+            return;
+        }
+
+        tokens.append(Punct::new(':', Spacing::Joint));
+        tokens.append(Punct::new(':', Spacing::Alone));
+        tokens.append(Punct::new('<', Spacing::Alone));
+        for param in params {
+            if param.is_lifetime() {
+                param.tk_prefix.to_tokens(tokens);
+            }
+            tokens.append(param.name.clone());
+            tokens.append(Punct::new(',', Spacing::Alone));
+        }
+        tokens.append(Punct::new('>', Spacing::Alone));
+    }
+}
+
+impl ToTokens for ImplGenerics<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let generic_params = match self.0 {
+            Some(generic_params) => generic_params,
+            None => return,
+        };
+
+        tokens.append(generic_params.tk_l_bracket.clone());
+        for param in generic_params.params.items() {
+            param.tk_prefix.to_tokens(tokens);
+            param.name.to_tokens(tokens);
+            param.bound.to_tokens(tokens);
+            tokens.append(Punct::new(',', Spacing::Alone));
+        }
+        tokens.append(generic_params.tk_r_bracket.clone());
+    }
+}
+
+impl ToTokens for TypeGenerics<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let Some(generic_params) = self.0 {
+            InlineGenericArgs(generic_params).to_tokens(tokens);
+        }
+    }
+}
+
 impl ToTokens for WhereClause {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.append(self.tk_where.clone());
@@ -1408,6 +1687,21 @@ impl ToTokens for UseDeclaration {
     }
 }
 
+impl ToTokens for MacroDef {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for attribute in &self.attributes {
+            attribute.to_tokens(tokens);
+        }
+        self.tk_macro_rules.to_tokens(tokens);
+        self.tk_bang.to_tokens(tokens);
+        self.name.to_tokens(tokens);
+        self.tk_rules_group.quote_with(tokens, |tokens| {
+            self.rules.to_tokens(tokens);
+        });
+        self.tk_semicolon.to_tokens(tokens);
+    }
+}
+
 impl ToTokens for EnumVariantValue {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         self.tk_equal.to_tokens(tokens);
@@ -1436,3 +1730,132 @@ impl Default for WhereClause {
         }
     }
 }
+
+// --- PartialEq and Hash impls ---
+
+// Spans are ignored: two values are equal if they print to the same tokens,
+// and hashing is done on that printed form.
+macro_rules! implement_token_eq_hash {
+    ($($Kind:ident),* $(,)?) => {
+        $(
+            /// Compares token content, ignoring spans.
+            impl PartialEq for $Kind {
+                fn eq(&self, other: &Self) -> bool {
+                    self.to_token_stream().to_string() == other.to_token_stream().to_string()
+                }
+            }
+
+            impl Eq for $Kind {}
+
+            /// Hashes token content, ignoring spans.
+            impl std::hash::Hash for $Kind {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    self.to_token_stream().to_string().hash(state);
+                }
+            }
+        )*
+    };
+}
+
+implement_token_eq_hash! {
+    Declaration,
+    Struct,
+    StructFields,
+    TupleStructFields,
+    NamedStructFields,
+    Enum,
+    EnumVariant,
+    Module,
+    Union,
+    Trait,
+    TraitMember,
+    Impl,
+    ImplMember,
+    ExternBlock,
+    ExternMember,
+    Constant,
+    TyDefinition,
+    Function,
+    FnQualifiers,
+    FnParam,
+    FnReceiverParam,
+    FnTypedParam,
+    TupleField,
+    NamedField,
+    Attribute,
+    AttributeValue,
+    VisMarker,
+    GenericParamList,
+    GenericParam,
+    GenericBound,
+    GenericArgList,
+    GenericArg,
+    WhereClause,
+    WhereClauseItem,
+    TyExpr,
+    ValueExpr,
+    Path,
+    PathSegment,
+    UseDeclaration,
+    MacroDef,
+    EnumVariantValue,
+}
+
+/// Compares token content, ignoring spans.
+impl PartialEq for Repr {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Repr::C, Repr::C) => true,
+            (Repr::Transparent, Repr::Transparent) => true,
+            (Repr::Int(ident), Repr::Int(other_ident)) => ident == other_ident,
+            (Repr::Packed(align), Repr::Packed(other_align)) => {
+                align.as_ref().map(Literal::to_string)
+                    == other_align.as_ref().map(Literal::to_string)
+            }
+            (Repr::Align(align), Repr::Align(other_align)) => {
+                align.to_string() == other_align.to_string()
+            }
+            (Repr::Other(tokens), Repr::Other(other_tokens)) => {
+                let tokens: TokenStream = tokens.iter().cloned().collect();
+                let other_tokens: TokenStream = other_tokens.iter().cloned().collect();
+                tokens.to_string() == other_tokens.to_string()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Repr {}
+
+/// Hashes token content, ignoring spans.
+impl std::hash::Hash for Repr {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Repr::C | Repr::Transparent => {}
+            Repr::Int(ident) => ident.hash(state),
+            Repr::Packed(align) => align.as_ref().map(Literal::to_string).hash(state),
+            Repr::Align(align) => align.to_string().hash(state),
+            Repr::Other(tokens) => {
+                let tokens: TokenStream = tokens.iter().cloned().collect();
+                tokens.to_string().hash(state);
+            }
+        }
+    }
+}
+
+/// Compares delimiters, ignoring spans.
+impl PartialEq for GroupSpan {
+    fn eq(&self, other: &Self) -> bool {
+        self.delimiter == other.delimiter
+    }
+}
+
+impl Eq for GroupSpan {}
+
+/// Hashes delimiters, ignoring spans.
+impl std::hash::Hash for GroupSpan {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(&self.delimiter).hash(state);
+    }
+}
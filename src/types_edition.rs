@@ -1,12 +1,18 @@
-use crate::parse_utils::{consume_path, tokens_from_slice};
+use crate::parse_utils::{consume_path, consume_punct, consume_stuff_until, tokens_from_slice};
 pub use crate::types::{
-    Attribute, AttributeValue, Declaration, Enum, EnumVariant, EnumVariantValue, Function,
-    GenericBound, GenericParam, GenericParamList, GroupSpan, InlineGenericArgs, NamedField, Struct,
-    StructFields, TupleField, TyExpr, Union, VisMarker, WhereClause, WhereClauseItem,
+    Attribute, AttributeValue, DeclKind, Declaration, Enum, EnumVariant, Field, Function,
+    GenericBound, GenericParam, GenericParamKind, GenericParamList, GroupSpan, ImplGenerics,
+    InlineGenericArgs, Struct, StructFields, TupleField, TurbofishGenericArgs, TyExpr,
+    TypeGenerics, Union, WhereClause, WhereClauseItem,
 };
-use crate::types::{FnQualifiers, GenericArg, GenericArgList, Impl, Module, Path};
-use crate::{Constant, Punctuated, Trait, TyDefinition};
-use proc_macro2::{Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use crate::types::{
+    EnumVariantValue, ExternBlock, ExternMember, FnParam, FnQualifiers, FnReceiverParam,
+    FnTypedParam, GenericArg, GenericArgList, Impl, ImplMember, MacroDef, Module, NamedField,
+    NamedStructFields, Path, Repr, TupleStructFields, UseDeclaration, VisMarker,
+};
+use crate::{Constant, Error, Punctuated, Trait, TraitMember, TyDefinition};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use quote::ToTokens;
 
 impl Declaration {
     /// Returns the [`Vec<Attribute>`] of the declaration.
@@ -18,10 +24,12 @@ impl Declaration {
             Declaration::Module(mod_decl) => &mod_decl.attributes,
             Declaration::Trait(trait_decl) => &trait_decl.attributes,
             Declaration::Impl(impl_decl) => &impl_decl.attributes,
+            Declaration::ExternBlock(extern_decl) => &extern_decl.attributes,
             Declaration::TyDefinition(ty_decl) => &ty_decl.attributes,
             Declaration::Function(function_decl) => &function_decl.attributes,
             Declaration::Constant(const_decl) => &const_decl.attributes,
             Declaration::Use(use_decl) => &use_decl.attributes,
+            Declaration::MacroDef(macro_decl) => &macro_decl.attributes,
         }
     }
 
@@ -34,19 +42,117 @@ impl Declaration {
             Declaration::Module(mod_decl) => &mut mod_decl.attributes,
             Declaration::Trait(trait_decl) => &mut trait_decl.attributes,
             Declaration::Impl(impl_decl) => &mut impl_decl.attributes,
+            Declaration::ExternBlock(extern_decl) => &mut extern_decl.attributes,
             Declaration::TyDefinition(ty_decl) => &mut ty_decl.attributes,
             Declaration::Function(function_decl) => &mut function_decl.attributes,
             Declaration::Constant(const_decl) => &mut const_decl.attributes,
             Declaration::Use(use_decl) => &mut use_decl.attributes,
+            Declaration::MacroDef(macro_decl) => &mut macro_decl.attributes,
         }
     }
 
+    /// Returns the first attribute whose last path segment is `name`.
+    ///
+    /// For instance, `get_attribute("skip")` will match both `#[skip]` and `#[serde::skip]`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     #[derive(Clone)]
+    ///     #[repr(C)]
+    ///     struct Hello;
+    /// )).unwrap();
+    /// let repr = struct_type.get_attribute("repr").unwrap();
+    /// assert_eq!(repr.get_value_tokens()[0].to_string(), "C");
+    /// assert!(!struct_type.has_attribute("serde"));
+    /// ```
+    pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
+        get_attribute_from_attributes(self.attributes(), name)
+    }
+
+    /// Returns true if the declaration has an attribute whose last path segment is `name`.
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.get_attribute(name).is_some()
+    }
+
+    /// Removes and returns the first attribute whose last path segment is `name`.
+    ///
+    /// Useful for attribute macros that need to strip their own helper
+    /// attributes before emitting the declaration again.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::{quote, ToTokens};
+    /// let mut struct_type = parse_declaration(quote!(
+    ///     #[my_macro(skip)]
+    ///     #[derive(Clone)]
+    ///     struct Hello;
+    /// )).unwrap();
+    /// let my_macro = struct_type.remove_attribute("my_macro").unwrap();
+    /// assert_eq!(my_macro.get_value_tokens()[0].to_string(), "skip");
+    /// assert_eq!(
+    ///     struct_type.to_token_stream().to_string(),
+    ///     "# [derive (Clone)] struct Hello ;"
+    /// );
+    /// ```
+    pub fn remove_attribute(&mut self, name: &str) -> Option<Attribute> {
+        remove_attribute_from_attributes(self.attributes_mut(), name)
+    }
+
+    /// Returns the concatenated values of all `#[doc = "..."]` attributes, if any.
+    ///
+    /// Doc comments such as `/// Hello` are lowered to `#[doc = " Hello"]` attributes,
+    /// so they're included too. The leading space of each line is removed, and lines
+    /// are separated by `\n`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     /// Hello
+    ///     #[doc = " world"]
+    ///     struct Hello;
+    /// )).unwrap();
+    /// assert_eq!(struct_type.doc_string().unwrap(), "Hello\nworld");
+    /// ```
+    pub fn doc_string(&self) -> Option<String> {
+        doc_string_from_attributes(self.attributes())
+    }
+
+    /// Returns the traits listed in all `#[derive(...)]` attributes, in order.
+    ///
+    /// Only the last segment of each path is returned, so `serde::Serialize`
+    /// yields `Serialize`. Use [`derive_paths`](Self::derive_paths) to get the
+    /// full paths.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     #[derive(Clone, Debug)]
+    ///     #[derive(serde::Serialize)]
+    ///     struct Hello;
+    /// )).unwrap();
+    /// let derives: Vec<_> = struct_type.derives().iter().map(|ident| ident.to_string()).collect();
+    /// assert_eq!(derives, ["Clone", "Debug", "Serialize"]);
+    /// ```
+    pub fn derives(&self) -> Vec<Ident> {
+        derives_from_attributes(self.attributes())
+    }
+
+    /// Returns the paths listed in all `#[derive(...)]` attributes, in order,
+    /// eg `serde::Serialize`.
+    pub fn derive_paths(&self) -> Vec<TokenStream> {
+        derive_paths_from_attributes(self.attributes())
+    }
+
     /// Returns the [`GenericParamList`], if any, of the declaration.
     ///
     /// For instance, this will return Some for `struct MyStruct<A, B, C> { ... }`,
     /// Some for `impl<A> MyTrait for MyType<A>` and None for `enum MyEnum { ... }`.
     ///
-    /// `TyDefinition` and `Constant` variants never have a generic parameter list.
+    /// `Constant` variants never have a generic parameter list.
     pub fn generic_params(&self) -> Option<&GenericParamList> {
         match self {
             Declaration::Struct(struct_decl) => struct_decl.generic_params.as_ref(),
@@ -55,10 +161,12 @@ impl Declaration {
             Declaration::Module(_) => None,
             Declaration::Trait(trait_decl) => trait_decl.generic_params.as_ref(),
             Declaration::Impl(impl_decl) => impl_decl.impl_generic_params.as_ref(),
-            Declaration::TyDefinition(_) => None,
+            Declaration::ExternBlock(_) => None,
+            Declaration::TyDefinition(ty_decl) => ty_decl.generic_params.as_ref(),
             Declaration::Function(function_decl) => function_decl.generic_params.as_ref(),
             Declaration::Constant(_) => None,
             Declaration::Use(_) => None,
+            Declaration::MacroDef(_) => None,
         }
     }
 
@@ -67,7 +175,7 @@ impl Declaration {
     /// For instance, this will return Some for `struct MyStruct<A, B, C> { ... }`,
     /// Some for `impl<A> MyTrait for MyType<A>` and None for `enum MyEnum { ... }`.
     ///
-    /// `TyDefinition` and `Constant` variants never have a generic parameter list.
+    /// `Constant` variants never have a generic parameter list.
     pub fn generic_params_mut(&mut self) -> Option<&mut GenericParamList> {
         match self {
             Declaration::Struct(struct_decl) => struct_decl.generic_params.as_mut(),
@@ -76,10 +184,12 @@ impl Declaration {
             Declaration::Module(_) => None,
             Declaration::Trait(trait_decl) => trait_decl.generic_params.as_mut(),
             Declaration::Impl(impl_decl) => impl_decl.impl_generic_params.as_mut(),
-            Declaration::TyDefinition(_) => None,
+            Declaration::ExternBlock(_) => None,
+            Declaration::TyDefinition(ty_decl) => ty_decl.generic_params.as_mut(),
             Declaration::Function(function_decl) => function_decl.generic_params.as_mut(),
             Declaration::Constant(_) => None,
             Declaration::Use(_) => None,
+            Declaration::MacroDef(_) => None,
         }
     }
 
@@ -104,13 +214,226 @@ impl Declaration {
             Declaration::Module(mod_decl) => Some(mod_decl.name.clone()),
             Declaration::Trait(trait_decl) => Some(trait_decl.name.clone()),
             Declaration::Impl(_) => None,
+            Declaration::ExternBlock(_) => None,
             Declaration::TyDefinition(ty_decl) => Some(ty_decl.name.clone()),
             Declaration::Function(function_decl) => Some(function_decl.name.clone()),
             Declaration::Constant(const_decl) => Some(const_decl.name.clone()),
             Declaration::Use(_) => None,
+            Declaration::MacroDef(macro_decl) => Some(macro_decl.name.clone()),
+        }
+    }
+
+    /// Replaces the name of the declaration and returns the previous one.
+    ///
+    /// Returns None and leaves the declaration unchanged if it doesn't have
+    /// a name, eg for an `impl` block. To keep pointing diagnostics at the
+    /// original item, create the new name with the span of the previous one.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::{format_ident, quote, ToTokens};
+    /// let mut struct_type = parse_declaration(quote!(
+    ///     struct Foo(u32);
+    /// )).unwrap();
+    /// let span = struct_type.name().unwrap().span();
+    /// struct_type.set_name(format_ident!("FooBuilder", span = span));
+    /// assert_eq!(struct_type.to_token_stream().to_string(), "struct FooBuilder (u32) ;");
+    /// ```
+    pub fn set_name(&mut self, name: Ident) -> Option<Ident> {
+        let current_name = match self {
+            Declaration::Struct(struct_decl) => &mut struct_decl.name,
+            Declaration::Enum(enum_decl) => &mut enum_decl.name,
+            Declaration::Union(union_decl) => &mut union_decl.name,
+            Declaration::Module(mod_decl) => &mut mod_decl.name,
+            Declaration::Trait(trait_decl) => &mut trait_decl.name,
+            Declaration::Impl(_) => return None,
+            Declaration::ExternBlock(_) => return None,
+            Declaration::TyDefinition(ty_decl) => &mut ty_decl.name,
+            Declaration::Function(function_decl) => &mut function_decl.name,
+            Declaration::Constant(const_decl) => &mut const_decl.name,
+            Declaration::Use(_) => return None,
+            Declaration::MacroDef(macro_decl) => &mut macro_decl.name,
+        };
+        Some(std::mem::replace(current_name, name))
+    }
+
+    /// Returns a [`Span`] covering the whole declaration.
+    ///
+    /// Joining spans is only possible on nightly compilers or outside of proc macros.
+    /// Otherwise, this returns the span of the declaration's name, or of its leading
+    /// keyword for declarations without a name.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, Error};
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     struct Hello(A, B);
+    /// )).unwrap();
+    /// let error = Error::new_at_span(declaration.span(), "structs are not supported");
+    /// ```
+    pub fn span(&self) -> Span {
+        match self {
+            Declaration::Struct(struct_decl) => struct_decl.span(),
+            Declaration::Enum(enum_decl) => enum_decl.span(),
+            Declaration::Union(union_decl) => union_decl.span(),
+            Declaration::Module(mod_decl) => mod_decl.span(),
+            Declaration::Trait(trait_decl) => trait_decl.span(),
+            Declaration::Impl(impl_decl) => impl_decl.span(),
+            Declaration::ExternBlock(extern_decl) => extern_decl.span(),
+            Declaration::TyDefinition(ty_decl) => ty_decl.span(),
+            Declaration::Function(function_decl) => function_decl.span(),
+            Declaration::Constant(const_decl) => const_decl.span(),
+            Declaration::Use(use_decl) => use_decl.span(),
+            Declaration::MacroDef(macro_decl) => macro_decl.span(),
+        }
+    }
+
+    /// Returns the kind of the declaration, eg [`DeclKind::Struct`] for a [`Struct`].
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, DeclKind};
+    /// # use quote::quote;
+    /// let declaration = parse_declaration(quote!(
+    ///     fn hello() {}
+    /// )).unwrap();
+    /// assert_eq!(declaration.kind(), DeclKind::Function);
+    /// ```
+    pub fn kind(&self) -> DeclKind {
+        match self {
+            Declaration::Struct(_) => DeclKind::Struct,
+            Declaration::Enum(_) => DeclKind::Enum,
+            Declaration::Union(_) => DeclKind::Union,
+            Declaration::Module(_) => DeclKind::Module,
+            Declaration::Trait(_) => DeclKind::Trait,
+            Declaration::Impl(_) => DeclKind::Impl,
+            Declaration::ExternBlock(_) => DeclKind::ExternBlock,
+            Declaration::TyDefinition(_) => DeclKind::TyDefinition,
+            Declaration::Function(_) => DeclKind::Function,
+            Declaration::Constant(_) => DeclKind::Constant,
+            Declaration::Use(_) => DeclKind::Use,
+            Declaration::MacroDef(_) => DeclKind::MacroDef,
         }
     }
 
+    /// Returns the lifetimes used in the signature of the declaration, without the apostrophe.
+    ///
+    /// This covers generic params, where clauses, field and variant types, and
+    /// the types in the signature of functions, constants, type aliases, traits
+    /// and impls. Bodies and nested items, such as the methods of an impl, are
+    /// not scanned.
+    ///
+    /// Lifetimes are listed once each, starting with those declared in the
+    /// generic params. The anonymous lifetime `'_`, `'static`, and lifetimes bound by a `for<...>`
+    /// such as `'c` in `for<'c> Fn(&'c u8)` are excluded.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello<'a, T: 'static>(&'a T, Foo<'_, 'b>) where 'b: 'a;
+    /// )).unwrap();
+    /// let lifetimes: Vec<_> = struct_type
+    ///     .used_lifetimes()
+    ///     .iter()
+    ///     .map(|lifetime| lifetime.to_string())
+    ///     .collect();
+    /// assert_eq!(lifetimes, ["a", "b"]);
+    /// ```
+    pub fn used_lifetimes(&self) -> Vec<Ident> {
+        let mut lifetimes = Vec::new();
+        collect_declaration_lifetimes(self, &mut lifetimes);
+        lifetimes
+    }
+
+    /// Calls `f` on every type in the declaration, in order of appearance.
+    ///
+    /// This covers field and variant types, function params and return types,
+    /// the types of constants and type aliases, the self type and trait of an
+    /// `impl`, and the items of modules, traits, impls and extern blocks.
+    ///
+    /// Generic bounds and where clauses are visited too. Since venial keeps
+    /// bounds as raw tokens, each bound is passed as a [`TyExpr`] holding all
+    /// of its tokens, eg `Clone + 'a`. Lifetime params, bounds made only of
+    /// lifetimes such as `T: 'a`, and the default values of const params,
+    /// which are expressions, are skipped.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::{quote, ToTokens};
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello<T: Clone> where Vec<T>: Debug {
+    ///         a: T,
+    ///         b: u8,
+    ///     }
+    /// )).unwrap();
+    ///
+    /// let mut types = Vec::new();
+    /// struct_type.walk_types(|ty| types.push(ty.to_token_stream().to_string()));
+    /// assert_eq!(types, ["Clone", "Vec < T >", "Debug", "T", "u8"]);
+    /// ```
+    pub fn walk_types(&self, mut f: impl FnMut(&TyExpr)) {
+        type_walk::declaration(self, &mut f);
+    }
+
+    /// Calls `f` on every attribute in the declaration, in order of appearance.
+    ///
+    /// This covers outer and inner attributes of the declaration, and the
+    /// attributes of its generic params, fields, variants, function params
+    /// and items.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let enum_type = parse_declaration(quote!(
+    ///     #[derive(Debug)]
+    ///     enum Hello {
+    ///         #[default]
+    ///         A,
+    ///         B { #[serde(skip)] b: u8 },
+    ///     }
+    /// )).unwrap();
+    ///
+    /// let mut names = Vec::new();
+    /// enum_type.walk_attributes(|attribute| {
+    ///     names.push(attribute.get_single_path_segment().unwrap().to_string())
+    /// });
+    /// assert_eq!(names, ["derive", "default", "serde"]);
+    /// ```
+    pub fn walk_attributes(&self, mut f: impl FnMut(&Attribute)) {
+        walk_declaration_attributes(self, &mut f);
+    }
+
+    /// Calls `f` on every type in the declaration, letting it rewrite them.
+    ///
+    /// Types are visited in the same positions and order as with
+    /// [`Declaration::walk_types`]. Bounds are passed as a [`TyExpr`] holding
+    /// all of their tokens, and whatever tokens `f` leaves in it become the new
+    /// bound.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, TyExpr};
+    /// # use quote::{quote, ToTokens};
+    /// let mut struct_type = parse_declaration(quote!(
+    ///     struct Hello {
+    ///         a: crate::Foo,
+    ///         b: u8,
+    ///     }
+    /// )).unwrap();
+    ///
+    /// struct_type.map_types(|ty| {
+    ///     if ty.to_token_stream().to_string() == "crate :: Foo" {
+    ///         *ty = TyExpr::from_tokens(quote!(my_crate::Foo));
+    ///     }
+    /// });
+    /// assert_eq!(
+    ///     struct_type.to_token_stream().to_string(),
+    ///     quote!(struct Hello { a: my_crate::Foo, b: u8, }).to_string(),
+    /// );
+    /// ```
+    pub fn map_types(&mut self, mut f: impl FnMut(&mut TyExpr)) {
+        type_map::declaration(self, &mut f);
+    }
+
     /// Returns the [`Struct`] variant of the enum if possible.
     pub fn as_struct(&self) -> Option<&Struct> {
         match self {
@@ -159,6 +482,14 @@ impl Declaration {
         }
     }
 
+    /// Returns the [`ExternBlock`] variant of the enum if possible.
+    pub fn as_extern_block(&self) -> Option<&ExternBlock> {
+        match self {
+            Declaration::ExternBlock(extern_decl) => Some(extern_decl),
+            _ => None,
+        }
+    }
+
     /// Returns the [`TyDefinition`] variant of the enum if possible.
     pub fn as_ty_definition(&self) -> Option<&TyDefinition> {
         match self {
@@ -182,10 +513,128 @@ impl Declaration {
             _ => None,
         }
     }
+
+    /// Returns the [`MacroDef`] variant of the enum if possible.
+    pub fn as_macro_def(&self) -> Option<&MacroDef> {
+        match self {
+            Declaration::MacroDef(macro_decl) => Some(macro_decl),
+            _ => None,
+        }
+    }
+
+    /// Returns the [`UseDeclaration`] variant of the enum if possible.
+    pub fn as_use(&self) -> Option<&UseDeclaration> {
+        match self {
+            Declaration::Use(use_decl) => Some(use_decl),
+            _ => None,
+        }
+    }
 }
 
 impl Struct {
-    /// Returns a collection of strings that can be used to exhaustively
+    /// Create a new unit struct from name, eg `struct Hello;`.
+    ///
+    /// ```
+    /// # use venial::Struct;
+    /// # use quote::ToTokens;
+    /// let struct_type = Struct::new("Hello");
+    /// assert_eq!(struct_type.to_token_stream().to_string(), "struct Hello ;");
+    /// ```
+    pub fn new(name: &str) -> Self {
+        Struct {
+            attributes: Vec::new(),
+            vis_marker: None,
+            tk_struct: Ident::new("struct", Span::call_site()),
+            name: new_ident(name),
+            generic_params: None,
+            where_clause: None,
+            fields: StructFields::Unit,
+            tk_semicolon: Some(Punct::new(';', Spacing::Alone)),
+        }
+    }
+
+    /// Builder method, add a [`NamedField`] to the struct.
+    ///
+    /// If the struct is a unit struct, it becomes a struct with named fields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct is a tuple struct.
+    pub fn with_named_field(mut self, field: NamedField) -> Self {
+        if matches!(self.fields, StructFields::Unit) {
+            self.fields = StructFields::Named(NamedStructFields {
+                fields: Punctuated::new(),
+                tk_braces: GroupSpan {
+                    span: Span::call_site(),
+                    delimiter: Delimiter::Brace,
+                },
+            });
+            self.tk_semicolon = None;
+        }
+
+        match &mut self.fields {
+            StructFields::Named(named_fields) => named_fields.fields.push(field, None),
+            _ => panic!("cannot add named field to tuple struct"),
+        }
+        self
+    }
+
+    /// Builder method, add a [`TupleField`] to the struct.
+    ///
+    /// If the struct is a unit struct, it becomes a tuple struct.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct has named fields.
+    pub fn with_tuple_field(mut self, field: TupleField) -> Self {
+        if matches!(self.fields, StructFields::Unit) {
+            self.fields = StructFields::Tuple(TupleStructFields {
+                fields: Punctuated::new(),
+                tk_parens: GroupSpan {
+                    span: Span::call_site(),
+                    delimiter: Delimiter::Parenthesis,
+                },
+            });
+            self.tk_semicolon = Some(Punct::new(';', Spacing::Alone));
+        }
+
+        match &mut self.fields {
+            StructFields::Tuple(tuple_fields) => tuple_fields.fields.push(field, None),
+            _ => panic!("cannot add tuple field to struct with named fields"),
+        }
+        self
+    }
+
+    /// Returns true for tuple structs, eg `struct Hello(A, B);`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello(A, B);
+    /// )).unwrap();
+    /// let struct_type = struct_type.as_struct().unwrap();
+    /// assert!(struct_type.is_tuple_struct());
+    /// assert!(!struct_type.is_named_struct());
+    /// assert!(!struct_type.is_unit_struct());
+    /// ```
+    pub fn is_tuple_struct(&self) -> bool {
+        self.fields.is_tuple()
+    }
+
+    /// Returns true for structs with named fields, eg `struct Hello { a: A }`.
+    ///
+    /// This includes structs with empty braces, eg `struct Hello {}`.
+    pub fn is_named_struct(&self) -> bool {
+        self.fields.is_named()
+    }
+
+    /// Returns true for unit structs, eg `struct Hello;`.
+    pub fn is_unit_struct(&self) -> bool {
+        self.fields.is_unit()
+    }
+
+    /// Returns an iterator over strings that can be used to exhaustively
     /// access the struct's fields.
     ///
     /// If the struct is a tuple struct, integer strings will be returned.
@@ -200,7 +649,7 @@ impl Struct {
     ///     }
     /// )).unwrap();
     /// let struct_type = struct_type.as_struct().unwrap();
-    /// let field_names: Vec<_> = struct_type.field_names().into_iter().collect();
+    /// let field_names: Vec<_> = struct_type.field_names().collect();
     /// assert_eq!(field_names, ["a", "b"]);
     /// ```
     ///
@@ -211,22 +660,11 @@ impl Struct {
     ///     struct Hello(Foo, Bar);
     /// )).unwrap();
     /// let tuple_type = tuple_type.as_struct().unwrap();
-    /// let field_names: Vec<_> = tuple_type.field_names().into_iter().collect();
+    /// let field_names: Vec<_> = tuple_type.field_names().collect();
     /// assert_eq!(field_names, ["0", "1"]);
     /// ```
-    pub fn field_names(&self) -> impl IntoIterator<Item = String> {
-        match &self.fields {
-            StructFields::Unit => Vec::new(),
-            StructFields::Tuple(tuple_fields) => {
-                let range = 0..tuple_fields.fields.len();
-                range.map(|i| i.to_string()).collect()
-            }
-            StructFields::Named(named_fields) => named_fields
-                .fields
-                .items()
-                .map(|field| field.name.to_string())
-                .collect(),
-        }
+    pub fn field_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.fields.field_names()
     }
 
     /// Returns a collection of tokens that can be used to exhaustively
@@ -248,21 +686,155 @@ impl Struct {
         }
     }
 
+    /// Returns an iterator over the struct's fields, whether named or tuple.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello(pub A, B);
+    /// )).unwrap();
+    /// let struct_type = struct_type.as_struct().unwrap();
+    /// for field in struct_type.fields_iter() {
+    ///     assert!(field.name().is_none());
+    /// }
+    /// assert_eq!(struct_type.fields_iter().filter(|field| field.vis().is_some()).count(), 1);
+    /// ```
+    pub fn fields_iter(&self) -> impl Iterator<Item = Field<'_>> {
+        self.fields.fields_iter()
+    }
+
     /// Returns a collection of references to the struct's field types.
+    ///
+    /// See [`Struct::field_types_iter`] to use iterator adapters directly.
     pub fn field_types(&self) -> impl IntoIterator<Item = &TyExpr> {
+        self.fields.field_types()
+    }
+
+    /// Returns an iterator over references to the struct's field types.
+    ///
+    /// The fields are borrowed lazily, nothing is collected.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::{quote, ToTokens};
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello(u8, Vec<u8>);
+    /// )).unwrap();
+    /// let struct_type = struct_type.as_struct().unwrap();
+    ///
+    /// let last_type = struct_type.field_types_iter().last().unwrap();
+    /// assert_eq!(last_type.to_token_stream().to_string(), "Vec < u8 >");
+    /// ```
+    pub fn field_types_iter(&self) -> impl Iterator<Item = &TyExpr> {
+        self.fields.field_types()
+    }
+
+    /// Returns the named field called `name`.
+    ///
+    /// Returns None if there is no such field, or if the struct doesn't have
+    /// named fields. The `r#` prefix of raw identifiers is ignored, so a field
+    /// declared as `r#type` is found with either `"type"` or `"r#type"`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::{quote, ToTokens};
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello {
+    ///         foo: Foo,
+    ///         bar: Bar,
+    ///     }
+    /// )).unwrap();
+    /// let struct_type = struct_type.as_struct().unwrap();
+    /// let bar = struct_type.get_field("bar").unwrap();
+    /// assert_eq!(bar.ty.to_token_stream().to_string(), "Bar");
+    /// assert!(struct_type.get_field("baz").is_none());
+    /// ```
+    pub fn get_field(&self, name: &str) -> Option<&NamedField> {
+        let name = name.strip_prefix("r#").unwrap_or(name);
         match &self.fields {
-            StructFields::Unit => Vec::new(),
-            StructFields::Tuple(tuple_fields) => {
-                tuple_fields.fields.items().map(|field| &field.ty).collect()
-            }
-            StructFields::Named(named_fields) => {
-                named_fields.fields.items().map(|field| &field.ty).collect()
-            }
+            StructFields::Named(named_fields) => named_fields.fields.items().find(|field| {
+                let field_name = field.name.to_string();
+                field_name.strip_prefix("r#").unwrap_or(&field_name) == name
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the tuple field at `index`.
+    ///
+    /// Returns None if the index is out of bounds, or if the struct isn't a
+    /// tuple struct.
+    pub fn get_tuple_field(&self, index: usize) -> Option<&TupleField> {
+        match &self.fields {
+            StructFields::Tuple(tuple_fields) => tuple_fields.fields.get(index),
+            _ => None,
+        }
+    }
+
+    /// Returns every identifier in type position within the struct's field types,
+    /// including generic arguments.
+    ///
+    /// Path prefixes, lifetimes and keywords are skipped, so `std::vec::Vec<&'a dyn Foo>`
+    /// yields `Vec` and `Foo`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello {
+    ///         a: Vec<Foo<Bar>>,
+    ///         b: (u8, [Baz; 4]),
+    ///     }
+    /// )).unwrap();
+    /// let idents: Vec<_> = struct_type.as_struct().unwrap()
+    ///     .referenced_idents()
+    ///     .map(|ident| ident.to_string())
+    ///     .collect();
+    /// assert_eq!(idents, ["Vec", "Foo", "Bar", "u8", "Baz"]);
+    /// ```
+    pub fn referenced_idents(&self) -> impl Iterator<Item = Ident> {
+        let mut idents = Vec::new();
+        for ty in self.field_types() {
+            collect_type_idents(ty.to_token_stream(), &mut idents);
         }
+        idents.into_iter()
     }
 }
 
 impl Enum {
+    /// Create a new enum with no variants from name, eg `enum Hello {}`.
+    ///
+    /// ```
+    /// # use venial::{Enum, EnumVariant};
+    /// # use quote::ToTokens;
+    /// let enum_type = Enum::new("Hello")
+    ///     .with_variant(EnumVariant::unit("A"))
+    ///     .with_variant(EnumVariant::unit("B"));
+    /// assert_eq!(enum_type.to_token_stream().to_string(), "enum Hello { A , B }");
+    /// ```
+    pub fn new(name: &str) -> Self {
+        Enum {
+            attributes: Vec::new(),
+            vis_marker: None,
+            tk_enum: Ident::new("enum", Span::call_site()),
+            name: new_ident(name),
+            generic_params: None,
+            where_clause: None,
+            tk_braces: GroupSpan {
+                span: Span::call_site(),
+                delimiter: Delimiter::Brace,
+            },
+            variants: Punctuated::new(),
+        }
+    }
+
+    /// Builder method, add an [`EnumVariant`] to the enum.
+    pub fn with_variant(mut self, variant: EnumVariant) -> Self {
+        self.variants.push(variant, None);
+        self
+    }
+
     /// Returns true if every single variant is empty.
     ///
     /// ```
@@ -282,7 +854,373 @@ impl Enum {
         }
         true
     }
-}
+
+    /// Returns true if every variant has an explicit discriminant, eg `A = 1`.
+    ///
+    /// Note that this is also true for an enum without variants.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let enum_type = parse_declaration(quote!(
+    ///     enum MyEnum { A = 1, B = 2 }
+    /// )).unwrap();
+    /// assert!(enum_type.as_enum().unwrap().has_explicit_discriminants());
+    ///
+    /// let enum_type = parse_declaration(quote!(
+    ///     enum MyEnum { A = 1, B }
+    /// )).unwrap();
+    /// assert!(!enum_type.as_enum().unwrap().has_explicit_discriminants());
+    /// ```
+    pub fn has_explicit_discriminants(&self) -> bool {
+        self.variants
+            .items()
+            .all(|variant| variant.discriminant().is_some())
+    }
+
+    /// Returns the number of variants.
+    pub fn variant_count(&self) -> usize {
+        self.variants.len()
+    }
+
+    /// Returns the variant called `name`, if any.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let enum_type = parse_declaration(quote!(
+    ///     enum MyEnum { A, B(u8), C { c: u8 } }
+    /// )).unwrap();
+    /// let enum_type = enum_type.as_enum().unwrap();
+    /// assert_eq!(enum_type.variant_count(), 3);
+    /// assert!(enum_type.get_variant("B").unwrap().get_single_type().is_some());
+    /// assert!(enum_type.get_variant("D").is_none());
+    /// ```
+    pub fn get_variant(&self, name: &str) -> Option<&EnumVariant> {
+        self.variants.items().find(|variant| variant.name == name)
+    }
+}
+
+impl Union {
+    /// Returns an iterator over the names of the union's fields.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let union_type = parse_declaration(quote!(
+    ///     union Hello {
+    ///         a: u32,
+    ///         b: f32,
+    ///     }
+    /// )).unwrap();
+    /// let union_type = union_type.as_union().unwrap();
+    /// let field_names: Vec<_> = union_type.field_names().collect();
+    /// assert_eq!(field_names, ["a", "b"]);
+    /// ```
+    pub fn field_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.fields
+            .fields
+            .items()
+            .map(|field| field.name.to_string())
+    }
+
+    /// Returns an iterator over references to the union's field types.
+    pub fn field_types(&self) -> impl Iterator<Item = &TyExpr> {
+        self.fields.fields.items().map(|field| &field.ty)
+    }
+
+    /// Returns an iterator over the union's fields, see [`Struct::fields_iter`].
+    ///
+    /// Union fields are always named.
+    pub fn fields_iter(&self) -> impl Iterator<Item = Field<'_>> {
+        self.fields.fields.items().map(Field::Named)
+    }
+}
+
+impl Impl {
+    /// Returns true if the block implements a trait, eg `impl MyTrait for MyType`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let impl_decl = parse_declaration(quote!(
+    ///     impl Clone for MyType {}
+    /// )).unwrap();
+    /// assert!(impl_decl.as_impl().unwrap().is_trait_impl());
+    /// ```
+    pub fn is_trait_impl(&self) -> bool {
+        self.trait_ty.is_some()
+    }
+
+    /// Tries to parse the implemented trait as a [`Path`], eg `std::fmt::Debug`.
+    ///
+    /// Returns `None` for inherent impls, or if the trait isn't a path.
+    pub fn trait_path(&self) -> Option<Path> {
+        self.trait_ty.as_ref()?.as_path()
+    }
+
+    /// Returns the tokens inside the braces of the `impl` block, including inner attributes.
+    pub fn body_tokens(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        for attribute in &self.inner_attributes {
+            attribute.to_tokens(&mut tokens);
+        }
+        for item in &self.body_items {
+            item.to_tokens(&mut tokens);
+        }
+        tokens
+    }
+
+    /// Returns true if the block is declared `unsafe impl`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let impl_decl = parse_declaration(quote!(
+    ///     unsafe impl Send for MyType {}
+    /// )).unwrap();
+    /// assert!(impl_decl.as_impl().unwrap().is_unsafe());
+    /// ```
+    pub fn is_unsafe(&self) -> bool {
+        self.tk_unsafe.is_some()
+    }
+
+    /// Returns the associated constants of the block, eg `const MAX: usize = 10;`.
+    pub fn assoc_consts(&self) -> impl Iterator<Item = &Constant> {
+        self.body_items.iter().filter_map(|item| match item {
+            ImplMember::Constant(constant) => Some(constant),
+            _ => None,
+        })
+    }
+}
+
+impl Trait {
+    /// Returns true if the trait is declared `unsafe trait`.
+    pub fn is_unsafe(&self) -> bool {
+        self.tk_unsafe.is_some()
+    }
+
+    /// Returns the associated constants of the trait, with or without a default value.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let trait_decl = parse_declaration(quote!(
+    ///     trait MyTrait {
+    ///         const MAX: usize;
+    ///         const MIN: usize = 0;
+    ///         fn limits() -> (usize, usize);
+    ///     }
+    /// )).unwrap();
+    /// let defaults: Vec<_> = trait_decl
+    ///     .as_trait()
+    ///     .unwrap()
+    ///     .assoc_consts()
+    ///     .map(|constant| (constant.name.to_string(), constant.has_value()))
+    ///     .collect();
+    /// assert_eq!(defaults, [("MAX".to_string(), false), ("MIN".to_string(), true)]);
+    /// ```
+    pub fn assoc_consts(&self) -> impl Iterator<Item = &Constant> {
+        self.body_items.iter().filter_map(|item| match item {
+            TraitMember::Constant(constant) => Some(constant),
+            _ => None,
+        })
+    }
+}
+
+impl Constant {
+    /// Returns true if the declaration uses the `static` keyword rather than `const`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let static_decl = parse_declaration(quote!(
+    ///     static mut COUNTER: u32 = 0;
+    /// )).unwrap();
+    /// let static_decl = static_decl.as_constant().unwrap();
+    /// assert!(static_decl.is_static());
+    /// assert!(static_decl.tk_mut.is_some());
+    /// ```
+    pub fn is_static(&self) -> bool {
+        self.tk_const_or_static == "static"
+    }
+
+    /// Returns true if the constant has a value, eg `const MIN: usize = 0;`.
+    ///
+    /// Returns false for constants declared without one, such as associated
+    /// constants in a trait, eg `const MAX: usize;`.
+    pub fn has_value(&self) -> bool {
+        self.initializer.is_some()
+    }
+}
+
+impl UseDeclaration {
+    /// Returns the first segment of the imported path, eg `std` in
+    /// `use std::collections::{HashMap, HashSet};`.
+    ///
+    /// Leading `::` are skipped. Returns `None` if the tree doesn't start with
+    /// a path segment, eg `use {a, b};` or `use ::{a, b};`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let use_decl = parse_declaration(quote!(
+    ///     pub use crate::types::{Struct, Enum};
+    /// )).unwrap();
+    /// let use_decl = use_decl.as_use().unwrap();
+    /// assert_eq!(use_decl.leading_segment().unwrap(), "crate");
+    /// ```
+    pub fn leading_segment(&self) -> Option<&Ident> {
+        let mut tokens = self
+            .import_tree
+            .tokens
+            .iter()
+            .skip_while(|token| match token {
+                TokenTree::Punct(punct) => punct.as_char() == ':',
+                _ => false,
+            });
+        match tokens.next() {
+            Some(TokenTree::Ident(ident)) => Some(ident),
+            _ => None,
+        }
+    }
+}
+
+impl Function {
+    /// See [`InlineGenericArgs`] for details.
+    pub fn get_inline_generic_args(&self) -> Option<InlineGenericArgs<'_>> {
+        Some(self.generic_params.as_ref()?.as_inline_args())
+    }
+
+    /// Returns the function's generic args in turbofish form, eg `::<T, N>`,
+    /// to forward a call to the function.
+    ///
+    /// Lifetimes are only included if `include_lifetimes` is true, since they
+    /// can't be given explicitly when the function has late-bound lifetimes.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::{quote, ToTokens};
+    /// let func = parse_declaration(quote!(
+    ///     fn hello<'a, T: Clone, const N: usize>(a: &'a [T; N]) {}
+    /// )).unwrap();
+    /// let func = func.as_function().unwrap();
+    /// let name = &func.name;
+    /// let args = func.get_turbofish_generic_args(false);
+    /// assert_eq!(
+    ///     quote!(#name #args (a)).to_string(),
+    ///     "hello :: < T , N , > (a)"
+    /// );
+    /// ```
+    pub fn get_turbofish_generic_args(&self, include_lifetimes: bool) -> TurbofishGenericArgs<'_> {
+        TurbofishGenericArgs {
+            generic_params: self.generic_params.as_ref(),
+            include_lifetimes,
+        }
+    }
+
+    /// Returns true if the function is declared `const fn`.
+    pub fn is_const(&self) -> bool {
+        self.qualifiers.tk_const.is_some()
+    }
+
+    /// Returns true if the function is declared `async fn`.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let function = parse_declaration(quote!(
+    ///     pub async unsafe fn hello() {}
+    /// )).unwrap();
+    /// let function = function.as_function().unwrap();
+    /// assert!(function.is_async());
+    /// assert!(function.is_unsafe());
+    /// assert!(!function.is_const());
+    /// ```
+    pub fn is_async(&self) -> bool {
+        self.qualifiers.tk_async.is_some()
+    }
+
+    /// Returns true if the function is declared `unsafe fn`.
+    pub fn is_unsafe(&self) -> bool {
+        self.qualifiers.tk_unsafe.is_some()
+    }
+
+    /// Returns true if the function is declared `extern fn` or `extern "ABI" fn`.
+    ///
+    /// The ABI string, if any, is stored in `self.qualifiers.extern_abi`.
+    pub fn is_extern(&self) -> bool {
+        self.qualifiers.tk_extern.is_some()
+    }
+
+    /// Returns true if the function has a body, as opposed to a signature
+    /// ending with `;`, such as a required trait method.
+    pub fn has_body(&self) -> bool {
+        self.body.is_some()
+    }
+
+    /// Returns the tokens inside the braces of the function body, if any.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let function = parse_declaration(quote!(
+    ///     fn hello() -> i32 { 40 + 2 }
+    /// )).unwrap();
+    /// let function = function.as_function().unwrap();
+    /// assert_eq!(function.body_tokens().unwrap().to_string(), "40 + 2");
+    /// ```
+    pub fn body_tokens(&self) -> Option<TokenStream> {
+        Some(self.body.as_ref()?.stream())
+    }
+
+    /// Builder method, replace the function body with `tokens`, wrapped in braces.
+    ///
+    /// The span of the previous body is kept, if there was one. If the function
+    /// ended with `;`, the semicolon is removed.
+    pub fn with_body_tokens(mut self, tokens: TokenStream) -> Self {
+        let span = match &self.body {
+            Some(body) => body.span(),
+            None => Span::call_site(),
+        };
+        let mut body = Group::new(Delimiter::Brace, tokens);
+        body.set_span(span);
+
+        self.body = Some(body);
+        self.tk_semicolon = None;
+        self
+    }
+}
+
+impl FnParam {
+    /// Returns the name the parameter is bound to, if it can be used as-is
+    /// to forward the argument, eg `x` in `x: u8` or `mut x: u8`.
+    ///
+    /// Returns None for `self` parameters, whether receivers or typed.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let func = parse_declaration(quote!(
+    ///     fn hello(&self, a: u8, mut b: u16) {}
+    /// )).unwrap();
+    /// let names: Vec<_> = func
+    ///     .as_function()
+    ///     .unwrap()
+    ///     .params
+    ///     .items()
+    ///     .map(|param| param.simple_ident().map(|name| name.to_string()))
+    ///     .collect();
+    /// assert_eq!(names, [None, Some("a".to_string()), Some("b".to_string())]);
+    /// ```
+    pub fn simple_ident(&self) -> Option<&Ident> {
+        match self {
+            FnParam::Receiver(_) => None,
+            FnParam::Typed(param) if param.name == "self" => None,
+            FnParam::Typed(param) => Some(&param.name),
+        }
+    }
+}
 
 macro_rules! implement_common_methods {
     ($Kind:ident) => {
@@ -351,16 +1289,79 @@ macro_rules! implement_common_methods {
                     .filter(|param| GenericParam::is_const(param))
             }
 
+            /// Returns the hints of all `#[repr(...)]` attributes, or None if there aren't any.
+            ///
+            /// ```
+            /// # use venial::{parse_declaration, Repr};
+            /// # use quote::quote;
+            /// let enum_type = parse_declaration(quote!(
+            ///     #[repr(C, u8)]
+            ///     enum MyEnum { A, B }
+            /// )).unwrap();
+            /// let repr = enum_type.as_enum().unwrap().repr().unwrap();
+            /// assert!(matches!(repr[0], Repr::C));
+            /// assert!(matches!(&repr[1], Repr::Int(ty) if ty == "u8"));
+            /// ```
+            pub fn repr(&self) -> Option<Vec<Repr>> {
+                let mut repr_attributes = self
+                    .attributes
+                    .iter()
+                    .filter(|attribute| {
+                        matches!(attribute.get_single_path_segment(), Some(ident) if ident == "repr")
+                    })
+                    .peekable();
+                repr_attributes.peek()?;
+
+                Some(
+                    repr_attributes
+                        .flat_map(|attribute| parse_repr_hints(attribute.get_value_tokens()))
+                        .collect(),
+                )
+            }
+
+            /// Returns the traits listed in all `#[derive(...)]` attributes, in order.
+            ///
+            /// See [`Declaration::derives`] for details.
+            pub fn derives(&self) -> Vec<Ident> {
+                derives_from_attributes(&self.attributes)
+            }
+
+            /// Returns the paths listed in all `#[derive(...)]` attributes, in order.
+            ///
+            /// See [`Declaration::derive_paths`] for details.
+            pub fn derive_paths(&self) -> Vec<TokenStream> {
+                derive_paths_from_attributes(&self.attributes)
+            }
+
             /// See [`InlineGenericArgs`] for details.
             pub fn get_inline_generic_args(&self) -> Option<InlineGenericArgs<'_>> {
                 Some(self.generic_params.as_ref()?.as_inline_args())
             }
 
+            /// Returns the generic params with their bounds, to be quoted in the
+            /// `impl<...>` position of a trait implementation.
+            ///
+            /// Default values are removed, since they aren't allowed on impl blocks.
+            /// Attributes of the params are removed too, to match
+            /// [`split_for_impl`](HasGenerics::split_for_impl).
+            /// The type position uses [`get_inline_generic_args`](Self::get_inline_generic_args).
+            pub fn impl_generics(&self) -> Option<GenericParamList> {
+                let mut generic_params = self.generic_params.clone()?;
+                for param in generic_params.params.items_mut() {
+                    param.attributes.clear();
+                    param.tk_equals = None;
+                    param.default_value = None;
+                }
+                Some(generic_params)
+            }
+
             /// Returns a where clause that can be quoted to form
             /// a `impl TRAIT for TYPE where ... { ... }` trait implementation.
             ///
             /// This takes the bounds of the current declaration and adds one bound
-            /// to `derived_trait` for every generic argument. For instance:
+            /// to `derived_trait` for every generic argument. Bounds on a param that
+            /// is already in the where clause are merged into its item, and bounds
+            /// that are already present are skipped. For instance:
             ///
             /// ```no_run
             /// struct MyStruct<T, U> where T: Clone {
@@ -373,250 +1374,1348 @@ macro_rules! implement_common_methods {
             /// # trait SomeTrait {}
             /// impl<T, U> SomeTrait for MyStruct<T, U>
             ///     // GENERATED WHERE CLAUSE
-            ///     where T: SomeTrait, U: SomeTrait, T: Clone
+            ///     where T: Clone + SomeTrait, U: SomeTrait
             /// {
             ///     // ...
             /// }
             /// ```
             pub fn create_derive_where_clause(&self, derived_trait: TokenStream) -> WhereClause {
-                let mut where_clause = self.where_clause.clone().unwrap_or_default();
+                self.create_derive_where_clause_for(derived_trait, GenericParam::is_ty)
+            }
 
-                for param in self.get_type_params() {
-                    let item = WhereClauseItem {
-                        left_side: vec![param.name.clone().into()],
-                        bound: GenericBound {
-                            tk_colon: Punct::new(':', Spacing::Alone),
-                            tokens: derived_trait.clone().into_iter().collect(),
-                        },
-                    };
+            /// Same as [`create_derive_where_clause`](Self::create_derive_where_clause),
+            /// but only adds a bound for the generic params matching `predicate`.
+            ///
+            /// Lifetime and const params can't take trait bounds, so they are
+            /// skipped even if they match `predicate`.
+            pub fn create_derive_where_clause_for(
+                &self,
+                derived_trait: TokenStream,
+                predicate: impl Fn(&GenericParam) -> bool,
+            ) -> WhereClause {
+                let mut where_clause = self.where_clause.clone().unwrap_or_default();
 
-                    where_clause = where_clause.with_item(item);
+                let params = self.generic_params.iter().flat_map(|params| params.params.items());
+                for param in params.filter(|param| param.is_ty() && predicate(param)) {
+                    let left_side = vec![param.name.clone().into()];
+                    add_derive_bound(&mut where_clause, left_side, &derived_trait);
                 }
 
                 where_clause
             }
+
+            /// Returns true if both declarations are equal once all their attributes,
+            /// including doc comments and the attributes of fields and variants, are
+            /// removed.
+            ///
+            /// Like `==`, this compares token content and ignores spans.
+            pub fn eq_ignoring_attrs(&self, other: &Self) -> bool {
+                let mut this = self.clone();
+                let mut other = other.clone();
+                this.clear_attributes();
+                other.clear_attributes();
+                this == other
+            }
         }
     };
 }
 
+/// Adds `left_side: derived_trait` to the where clause.
+///
+/// If the where clause already has an item for `left_side`, the bound is merged
+/// into it, unless it's already present.
+fn add_derive_bound(
+    where_clause: &mut WhereClause,
+    left_side: Vec<TokenTree>,
+    derived_trait: &TokenStream,
+) {
+    let tokens_str =
+        |tokens: &[TokenTree]| TokenStream::from_iter(tokens.iter().cloned()).to_string();
+    let left_side_str = tokens_str(&left_side);
+    let derived_trait_str = derived_trait.to_string();
+
+    let existing_item = where_clause
+        .items_mut()
+        .find(|item| tokens_str(&item.left_side) == left_side_str);
+    match existing_item {
+        Some(item) => {
+            let has_bound = item
+                .bound
+                .split()
+                .iter()
+                .any(|bound| bound.to_string() == derived_trait_str);
+            if has_bound {
+                return;
+            }
+            if !item.bound.tokens.is_empty() {
+                item.bound
+                    .tokens
+                    .push(Punct::new('+', Spacing::Alone).into());
+            }
+            item.bound.tokens.extend(derived_trait.clone());
+        }
+        None => {
+            where_clause.items.push(
+                WhereClauseItem {
+                    left_side,
+                    bound: GenericBound {
+                        tk_colon: Punct::new(':', Spacing::Alone),
+                        tokens: derived_trait.clone().into_iter().collect(),
+                    },
+                },
+                None,
+            );
+        }
+    }
+}
+
+fn derive_paths_from_attributes(attributes: &[Attribute]) -> Vec<TokenStream> {
+    attributes
+        .iter()
+        .filter(|attribute| {
+            matches!(attribute.get_single_path_segment(), Some(ident) if ident == "derive")
+        })
+        .flat_map(|attribute| {
+            attribute
+                .get_value_tokens()
+                .split(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+                .filter(|path| !path.is_empty())
+                .map(|path| path.iter().cloned().collect())
+        })
+        .collect()
+}
+
+fn derives_from_attributes(attributes: &[Attribute]) -> Vec<Ident> {
+    derive_paths_from_attributes(attributes)
+        .into_iter()
+        .filter_map(|path| {
+            path.into_iter()
+                .filter_map(|token| match token {
+                    TokenTree::Ident(ident) => Some(ident),
+                    _ => None,
+                })
+                .last()
+        })
+        .collect()
+}
+
+fn parse_repr_hints(tokens: &[TokenTree]) -> Vec<Repr> {
+    tokens
+        .split(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+        .filter(|hint| !hint.is_empty())
+        .map(|hint| match hint {
+            [TokenTree::Ident(ident)] if ident == "C" => Repr::C,
+            [TokenTree::Ident(ident)] if ident == "transparent" => Repr::Transparent,
+            [TokenTree::Ident(ident)]
+                if matches!(
+                    ident.to_string().as_str(),
+                    "u8" | "u16"
+                        | "u32"
+                        | "u64"
+                        | "u128"
+                        | "usize"
+                        | "i8"
+                        | "i16"
+                        | "i32"
+                        | "i64"
+                        | "i128"
+                        | "isize"
+                ) =>
+            {
+                Repr::Int(ident.clone())
+            }
+            [TokenTree::Ident(ident)] if ident == "packed" => Repr::Packed(None),
+            [TokenTree::Ident(ident), TokenTree::Group(group)]
+                if ident == "packed" || ident == "align" =>
+            {
+                let mut group_tokens = group.stream().into_iter();
+                match (group_tokens.next(), group_tokens.next()) {
+                    (Some(TokenTree::Literal(literal)), None) if ident == "packed" => {
+                        Repr::Packed(Some(literal))
+                    }
+                    (Some(TokenTree::Literal(literal)), None) => Repr::Align(literal),
+                    _ => Repr::Other(hint.to_vec()),
+                }
+            }
+            _ => Repr::Other(hint.to_vec()),
+        })
+        .collect()
+}
+
 implement_common_methods! { Struct }
 implement_common_methods! { Enum }
 implement_common_methods! { Union }
 
-impl Attribute {
-    /// Returns Some if the attribute has a single path segment, eg `#[hello(...)]`.
-    /// Returns None if the attribute has multiple segments, eg `#[hello::world(...)]`.
-    pub fn get_single_path_segment(&self) -> Option<&Ident> {
-        let mut segments: Vec<_> = self
-            .path
-            .iter()
-            .filter_map(|segment| match segment {
-                TokenTree::Ident(ident) => Some(ident),
-                _ => None,
-            })
-            .collect();
-        if segments.len() == 1 {
-            segments.pop()
-        } else {
-            None
+impl Struct {
+    fn clear_attributes(&mut self) {
+        self.attributes.clear();
+        if let Some(generic_params) = &mut self.generic_params {
+            generic_params.clear_attributes();
         }
-    }
-
-    /// Returns `foo + bar` for `#[hello = foo + bar]` and `#[hello(foo + bar)]`.
-    /// Returns an empty slice for `#[hello]`.
-    pub fn get_value_tokens(&self) -> &[TokenTree] {
-        self.value.get_value_tokens()
+        self.fields.clear_attributes();
     }
 }
 
-impl AttributeValue {
-    /// Returns `foo + bar` for `#[hello = foo + bar]` and `#[hello(foo + bar)]`.
-    /// Returns an empty slice for `#[hello]`.
-    pub fn get_value_tokens(&self) -> &[TokenTree] {
-        match self {
-            AttributeValue::Group(_, tokens) => tokens,
-            AttributeValue::Equals(_, tokens) => tokens,
-            AttributeValue::Empty => &[],
+impl Enum {
+    fn clear_attributes(&mut self) {
+        self.attributes.clear();
+        if let Some(generic_params) = &mut self.generic_params {
+            generic_params.clear_attributes();
+        }
+        for variant in self.variants.items_mut() {
+            variant.attributes.clear();
+            variant.contents.clear_attributes();
         }
     }
 }
 
-impl EnumVariant {
-    /// Returns true if the variant doesn't store a type.
-    pub fn is_empty_variant(&self) -> bool {
-        matches!(self.contents, StructFields::Unit)
+impl Union {
+    fn clear_attributes(&mut self) {
+        self.attributes.clear();
+        if let Some(generic_params) = &mut self.generic_params {
+            generic_params.clear_attributes();
+        }
+        for field in self.fields.fields.items_mut() {
+            field.attributes.clear();
+        }
     }
+}
 
-    /// Returns Some if the variant is a wrapper around a single type.
-    /// Returns None otherwise.
-    pub fn get_single_type(&self) -> Option<&TupleField> {
-        match &self.contents {
-            StructFields::Tuple(fields) if fields.fields.len() == 1 => Some(&fields.fields[0].0),
-            StructFields::Tuple(_fields) => None,
-            StructFields::Unit => None,
-            StructFields::Named(_) => None,
+impl GenericParamList {
+    fn clear_attributes(&mut self) {
+        for param in self.params.items_mut() {
+            param.attributes.clear();
         }
     }
 }
 
-impl FnQualifiers {
-    /// Whether exactly either `const` or `unsafe` attribute is set, and no other one
-    /// (so the tokens could be the start of a constant or impl declaration)
-    pub(crate) fn has_only_const_xor_unsafe(&self) -> bool {
-        (self.tk_const.is_some() ^ self.tk_unsafe.is_some())
-            && self.tk_default.is_none()
-            && self.tk_async.is_none()
-            && self.tk_extern.is_none()
-            && self.extern_abi.is_none()
+impl StructFields {
+    fn clear_attributes(&mut self) {
+        match self {
+            StructFields::Unit => {}
+            StructFields::Tuple(tuple_fields) => {
+                for field in tuple_fields.fields.items_mut() {
+                    field.attributes.clear();
+                }
+            }
+            StructFields::Named(named_fields) => {
+                for field in named_fields.fields.items_mut() {
+                    field.attributes.clear();
+                }
+            }
+        }
     }
 }
 
-impl GenericParamList {
-    /// Builder method, add a [`GenericParam`].
+macro_rules! implement_attribute_methods {
+    ($Kind:ident) => {
+        impl $Kind {
+            /// Returns the [`Vec<Attribute>`] of the item.
+            pub fn attributes(&self) -> &Vec<Attribute> {
+                &self.attributes
+            }
+
+            /// Returns the first attribute whose last path segment is `name`.
+            ///
+            /// See [`Declaration::get_attribute`] for details.
+            pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
+                get_attribute_from_attributes(&self.attributes, name)
+            }
+
+            /// Returns true if there is an attribute whose last path segment is `name`.
+            pub fn has_attribute(&self, name: &str) -> bool {
+                self.get_attribute(name).is_some()
+            }
+
+            /// Removes and returns the first attribute whose last path segment is `name`.
+            ///
+            /// See [`Declaration::remove_attribute`] for details.
+            pub fn remove_attribute(&mut self, name: &str) -> Option<Attribute> {
+                remove_attribute_from_attributes(&mut self.attributes, name)
+            }
+
+            /// Returns the concatenated values of all `#[doc = "..."]` attributes, if any.
+            ///
+            /// See [`Declaration::doc_string`] for details.
+            pub fn doc_string(&self) -> Option<String> {
+                doc_string_from_attributes(&self.attributes)
+            }
+        }
+    };
+}
+
+implement_attribute_methods! { Struct }
+implement_attribute_methods! { Enum }
+implement_attribute_methods! { EnumVariant }
+implement_attribute_methods! { Union }
+implement_attribute_methods! { Module }
+implement_attribute_methods! { Trait }
+implement_attribute_methods! { Impl }
+implement_attribute_methods! { ExternBlock }
+implement_attribute_methods! { Constant }
+implement_attribute_methods! { TyDefinition }
+implement_attribute_methods! { Function }
+implement_attribute_methods! { FnReceiverParam }
+implement_attribute_methods! { FnTypedParam }
+implement_attribute_methods! { NamedField }
+implement_attribute_methods! { TupleField }
+implement_attribute_methods! { GenericParam }
+implement_attribute_methods! { UseDeclaration }
+implement_attribute_methods! { MacroDef }
+
+macro_rules! implement_span_method {
+    ($Kind:ident, $fallback:ident) => {
+        impl $Kind {
+            /// Returns a [`Span`] covering the whole item.
+            ///
+            /// See [`Declaration::span`] for details.
+            pub fn span(&self) -> Span {
+                joined_span(self.to_token_stream(), self.$fallback.span())
+            }
+        }
+    };
+}
+
+implement_span_method! { Struct, name }
+implement_span_method! { Enum, name }
+implement_span_method! { Union, name }
+implement_span_method! { Module, name }
+implement_span_method! { Trait, name }
+implement_span_method! { Impl, tk_impl }
+implement_span_method! { ExternBlock, tk_extern }
+implement_span_method! { TyDefinition, name }
+implement_span_method! { Function, name }
+implement_span_method! { Constant, name }
+implement_span_method! { UseDeclaration, tk_use }
+implement_span_method! { MacroDef, name }
+
+macro_rules! implement_with_name {
+    ($Kind:ident) => {
+        impl $Kind {
+            /// Builder method, replace the item's name.
+            ///
+            /// See [`Declaration::set_name`] for details.
+            pub fn with_name(mut self, name: Ident) -> Self {
+                self.name = name;
+                self
+            }
+        }
+    };
+}
+
+implement_with_name! { Struct }
+implement_with_name! { Enum }
+implement_with_name! { Union }
+implement_with_name! { Module }
+implement_with_name! { Trait }
+implement_with_name! { TyDefinition }
+implement_with_name! { Function }
+implement_with_name! { Constant }
+implement_with_name! { MacroDef }
+
+/// Common access to the generics of [`Struct`], [`Enum`], [`Union`], [`Trait`] and [`Impl`].
+pub trait HasGenerics {
+    /// Returns the [`GenericParamList`] of the declaration, if any.
     ///
-    /// Add lifetime params to the beginning of the list.
-    pub fn with_param(mut self, param: GenericParam) -> Self {
-        if param.is_lifetime() {
-            self.params.insert(0, param, None);
+    /// For an [`Impl`], these are the params after the `impl` keyword.
+    fn generic_params(&self) -> Option<&GenericParamList>;
+
+    /// Returns the [`WhereClause`] of the declaration, if any.
+    fn where_clause(&self) -> Option<&WhereClause>;
+
+    /// Splits the generics into the parts of a trait implementation header, similar
+    /// to syn's `Generics::split_for_impl`.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, HasGenerics};
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello<'a, T: Clone = i32>(&'a T) where T: Debug;
+    /// )).unwrap();
+    /// let struct_type = struct_type.as_struct().unwrap();
+    /// let name = &struct_type.name;
+    /// let (impl_generics, ty_generics, where_clause) = struct_type.split_for_impl();
+    /// let impl_header = quote!(impl #impl_generics MyTrait for #name #ty_generics #where_clause);
+    /// assert_eq!(
+    ///     impl_header.to_string(),
+    ///     "impl < 'a , T : Clone , > MyTrait for Hello < 'a , T , > where T : Debug"
+    /// );
+    /// ```
+    fn split_for_impl(&self) -> (ImplGenerics<'_>, TypeGenerics<'_>, Option<&WhereClause>) {
+        let generic_params = self.generic_params();
+        (
+            ImplGenerics(generic_params),
+            TypeGenerics(generic_params),
+            self.where_clause(),
+        )
+    }
+}
+
+macro_rules! implement_has_generics {
+    ($Kind:ident, $generic_params:ident) => {
+        impl HasGenerics for $Kind {
+            fn generic_params(&self) -> Option<&GenericParamList> {
+                self.$generic_params.as_ref()
+            }
+
+            fn where_clause(&self) -> Option<&WhereClause> {
+                self.where_clause.as_ref()
+            }
+        }
+    };
+}
+
+implement_has_generics! { Struct, generic_params }
+implement_has_generics! { Enum, generic_params }
+implement_has_generics! { Union, generic_params }
+implement_has_generics! { Trait, generic_params }
+implement_has_generics! { Impl, impl_generic_params }
+
+fn get_attribute_from_attributes<'a>(
+    attributes: &'a [Attribute],
+    name: &str,
+) -> Option<&'a Attribute> {
+    attributes.iter().find(
+        |attribute| matches!(attribute.get_path_segments().last(), Some(ident) if ident == name),
+    )
+}
+
+fn remove_attribute_from_attributes(
+    attributes: &mut Vec<Attribute>,
+    name: &str,
+) -> Option<Attribute> {
+    let index = attributes.iter().position(
+        |attribute| matches!(attribute.get_path_segments().last(), Some(ident) if ident == name),
+    )?;
+    Some(attributes.remove(index))
+}
+
+fn doc_string_from_attributes(attributes: &[Attribute]) -> Option<String> {
+    let mut lines = attributes
+        .iter()
+        .filter_map(Attribute::get_doc_line)
+        .peekable();
+    lines.peek()?;
+
+    let lines: Vec<_> = lines
+        .map(|line| match line.strip_prefix(' ') {
+            Some(stripped) => stripped.to_string(),
+            None => line,
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+impl Attribute {
+    /// Returns Some if the attribute has a single path segment, eg `#[hello(...)]`.
+    /// Returns None if the attribute has multiple segments, eg `#[hello::world(...)]`.
+    pub fn get_single_path_segment(&self) -> Option<&Ident> {
+        let mut segments: Vec<_> = self.get_path_segments().collect();
+        if segments.len() == 1 {
+            segments.pop()
         } else {
-            self.params.push(param, None);
+            None
         }
-        self
     }
 
-    /// See [`InlineGenericArgs`] for details.
-    pub fn as_inline_args(&self) -> InlineGenericArgs<'_> {
-        InlineGenericArgs(self)
+    /// Returns the identifiers of the attribute's path.
+    ///
+    /// For instance, returns `serde`, `skip` for `#[serde::skip]`.
+    pub fn get_path_segments(&self) -> impl Iterator<Item = &Ident> {
+        self.path.iter().filter_map(|segment| match segment {
+            TokenTree::Ident(ident) => Some(ident),
+            _ => None,
+        })
     }
-}
 
-impl GenericParam {
-    /// Create new lifetime param from name.
+    /// Returns `foo + bar` for `#[hello = foo + bar]` and `#[hello(foo + bar)]`.
+    /// Returns an empty slice for `#[hello]`.
+    pub fn get_value_tokens(&self) -> &[TokenTree] {
+        self.value.get_value_tokens()
+    }
+
+    /// Returns the string value of a `#[doc = "..."]` attribute, as written.
     ///
-    /// ```
-    /// # use venial::GenericParam;
-    /// GenericParam::lifetime("a")
-    /// # ;
-    /// ```
-    pub fn lifetime(name: &str) -> Self {
-        let lifetime_ident = Ident::new(name, Span::call_site());
-        GenericParam {
-            tk_prefix: Some(Punct::new('\'', Spacing::Joint).into()),
-            name: lifetime_ident,
-            bound: None,
+    /// Returns None for other attributes, or if the value isn't a string literal.
+    fn get_doc_line(&self) -> Option<String> {
+        match self.get_single_path_segment() {
+            Some(ident) if ident == "doc" => (),
+            _ => return None,
+        }
+        match &self.value {
+            AttributeValue::Equals(_, tokens) => match tokens.as_slice() {
+                [TokenTree::Literal(literal)] => parse_string_literal(literal),
+                _ => None,
+            },
+            _ => None,
         }
     }
 
-    /// Create new lifetime param from name and bound.
+    /// Returns true for inner attributes, eg `#![hello]`.
+    ///
+    /// Inner attributes are stored in the `inner_attributes` of the item
+    /// they apply to, eg the enclosing [`Module`] or [`Impl`].
+    pub fn is_inner(&self) -> bool {
+        self.tk_bang.is_some()
+    }
+
+    /// Returns true for outer attributes, eg `#[hello]`.
+    pub fn is_outer(&self) -> bool {
+        self.tk_bang.is_none()
+    }
+
+    /// Returns true for conditional compilation attributes, eg `#[cfg(test)]`.
+    ///
+    /// `#[cfg_attr(...)]` attributes don't count.
+    pub fn is_cfg(&self) -> bool {
+        matches!(self.get_single_path_segment(), Some(ident) if ident == "cfg")
+    }
+
+    /// Returns the predicate of a `#[cfg(...)]` attribute, eg `feature = "x"`
+    /// for `#[cfg(feature = "x")]`.
+    ///
+    /// Returns None for other attributes.
     ///
     /// ```
-    /// # use venial::GenericParam;
+    /// # use venial::parse_declaration;
     /// # use quote::quote;
-    /// GenericParam::bounded_lifetime("a", quote!(b + c).into_iter().collect())
-    /// # ;
+    /// let struct_type = parse_declaration(quote!(
+    ///     #[cfg(all(unix, feature = "x"))]
+    ///     #[derive(Debug)]
+    ///     struct Hello;
+    /// )).unwrap();
+    /// let attributes = struct_type.attributes();
+    /// assert_eq!(
+    ///     attributes[0].cfg_predicate().unwrap().to_string(),
+    ///     "all (unix , feature = \"x\")"
+    /// );
+    /// assert!(attributes[1].cfg_predicate().is_none());
     /// ```
-    pub fn bounded_lifetime(name: &str, bound: Vec<TokenTree>) -> Self {
-        let lifetime_ident = Ident::new(name, Span::call_site());
-        GenericParam {
-            tk_prefix: Some(Punct::new('\'', Spacing::Alone).into()),
-            name: lifetime_ident,
-            bound: Some(GenericBound {
-                tk_colon: Punct::new(':', Spacing::Alone),
-                tokens: bound,
-            }),
+    pub fn cfg_predicate(&self) -> Option<TokenStream> {
+        if !self.is_cfg() {
+            return None;
+        }
+        match &self.value {
+            AttributeValue::Group(group_span, tokens)
+                if group_span.delimiter == Delimiter::Parenthesis =>
+            {
+                Some(tokens.iter().cloned().collect())
+            }
+            _ => None,
         }
     }
+}
 
-    /// Create new type param from name.
-    ///
-    /// ```
-    /// # use venial::GenericParam;
-    /// GenericParam::ty("T")
-    /// # ;
-    /// ```
-    pub fn ty(name: &str) -> Self {
-        let ty_ident = Ident::new(name, Span::call_site());
-        GenericParam {
-            tk_prefix: None,
-            name: ty_ident,
-            bound: None,
+impl AttributeValue {
+    /// Returns `foo + bar` for `#[hello = foo + bar]` and `#[hello(foo + bar)]`.
+    /// Returns an empty slice for `#[hello]`.
+    pub fn get_value_tokens(&self) -> &[TokenTree] {
+        match self {
+            AttributeValue::Group(_, tokens) => tokens,
+            AttributeValue::Equals(_, tokens) => tokens,
+            AttributeValue::Empty => &[],
         }
     }
+}
 
-    /// Create new type param from name and bound.
+impl StructFields {
+    /// Returns the number of fields, 0 for unit structs.
     ///
     /// ```
-    /// # use venial::GenericParam;
+    /// # use venial::parse_declaration;
     /// # use quote::quote;
-    /// GenericParam::bounded_ty("T", quote!(Debug + Eq).into_iter().collect())
-    /// # ;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello(A, B);
+    /// )).unwrap();
+    /// let fields = &struct_type.as_struct().unwrap().fields;
+    /// assert_eq!(fields.len(), 2);
+    /// assert!(fields.is_tuple());
     /// ```
-    pub fn bounded_ty(name: &str, bound: Vec<TokenTree>) -> Self {
-        let ty_ident = Ident::new(name, Span::call_site());
-        GenericParam {
-            tk_prefix: None,
-            name: ty_ident,
-            bound: Some(GenericBound {
-                tk_colon: Punct::new(':', Spacing::Alone),
-                tokens: bound,
-            }),
+    pub fn len(&self) -> usize {
+        match self {
+            StructFields::Unit => 0,
+            StructFields::Tuple(tuple_fields) => tuple_fields.fields.len(),
+            StructFields::Named(named_fields) => named_fields.fields.len(),
         }
     }
 
-    /// Create new const param from name and type.
+    /// Returns true if there are no fields, eg `struct Hello;` or `struct Hello {}`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true for named fields, eg `struct Hello { a: A }`.
+    pub fn is_named(&self) -> bool {
+        matches!(self, StructFields::Named(_))
+    }
+
+    /// Returns true for tuple fields, eg `struct Hello(A);`.
+    pub fn is_tuple(&self) -> bool {
+        matches!(self, StructFields::Tuple(_))
+    }
+
+    /// Returns true for unit structs, eg `struct Hello;`.
+    pub fn is_unit(&self) -> bool {
+        matches!(self, StructFields::Unit)
+    }
+
+    /// Returns the delimiter of the fields' group, or None for unit structs.
+    ///
+    /// Tuple fields are delimited by [`Delimiter::Parenthesis`], named fields
+    /// by [`Delimiter::Brace`].
+    pub fn delimiter(&self) -> Option<Delimiter> {
+        match self {
+            StructFields::Unit => None,
+            StructFields::Tuple(tuple_fields) => Some(tuple_fields.tk_parens.delimiter),
+            StructFields::Named(named_fields) => Some(named_fields.tk_braces.delimiter),
+        }
+    }
+
+    /// Returns an iterator over the field names, see [`Struct::field_names`].
+    ///
+    /// Tuple fields are named after their index, eg `"0"`, `"1"`.
+    pub fn field_names(&self) -> impl Iterator<Item = String> + '_ {
+        let (len, named_fields) = match self {
+            StructFields::Unit => (0, None),
+            StructFields::Tuple(tuple_fields) => (tuple_fields.fields.len(), None),
+            StructFields::Named(named_fields) => (named_fields.fields.len(), Some(named_fields)),
+        };
+
+        (0..len).map(move |i| match named_fields {
+            Some(named_fields) => named_fields.fields[i].0.name.to_string(),
+            None => i.to_string(),
+        })
+    }
+
+    /// Returns an iterator over references to the field types.
+    pub fn field_types(&self) -> impl Iterator<Item = &TyExpr> {
+        self.fields_iter().map(|field| field.ty())
+    }
+
+    /// Returns an iterator over the fields, whether named or tuple.
+    pub fn fields_iter(&self) -> impl Iterator<Item = Field<'_>> {
+        let (named_fields, tuple_fields) = match self {
+            StructFields::Unit => (None, None),
+            StructFields::Tuple(tuple_fields) => (None, Some(tuple_fields)),
+            StructFields::Named(named_fields) => (Some(named_fields), None),
+        };
+
+        let named_fields = named_fields
+            .into_iter()
+            .flat_map(|named_fields| named_fields.fields.items().map(Field::Named));
+        let tuple_fields = tuple_fields
+            .into_iter()
+            .flat_map(|tuple_fields| tuple_fields.fields.items().map(Field::Tuple));
+        named_fields.chain(tuple_fields)
+    }
+}
+
+impl NamedField {
+    /// Create a new field from name and type, with no attributes or visibility marker.
     ///
     /// ```
-    /// # use venial::GenericParam;
+    /// # use venial::{NamedField, TyExpr};
     /// # use quote::quote;
-    /// GenericParam::const_param("N", quote!(i32).into_iter().collect())
+    /// NamedField::new("foo", TyExpr::from_tokens(quote!(Vec<u8>)))
     /// # ;
     /// ```
-    pub fn const_param(name: &str, ty: Vec<TokenTree>) -> Self {
-        let lifetime_ident = Ident::new(name, Span::call_site());
-        GenericParam {
-            tk_prefix: Some(Ident::new("const", Span::call_site()).into()),
-            name: lifetime_ident,
-            bound: Some(GenericBound {
-                tk_colon: Punct::new(':', Spacing::Alone),
-                tokens: ty,
-            }),
+    pub fn new(name: &str, ty: TyExpr) -> Self {
+        NamedField {
+            attributes: Vec::new(),
+            vis_marker: None,
+            name: new_ident(name),
+            tk_colon: Punct::new(':', Spacing::Alone),
+            ty,
         }
     }
 
-    /// Returns true if the generic param is a lifetime param.
-    pub fn is_lifetime(&self) -> bool {
-        matches!(
-            &self.tk_prefix,
-            Some(TokenTree::Punct(punct)) if punct.as_char() == '\''
-        )
+    /// Builder method, set the field's [`VisMarker`].
+    pub fn with_vis(mut self, vis_marker: VisMarker) -> Self {
+        self.vis_marker = Some(vis_marker);
+        self
     }
 
-    /// Returns true if the generic param is a type param.
-    pub fn is_ty(&self) -> bool {
-        self.tk_prefix.is_none()
+    /// Builder method, add an [`Attribute`] to the field.
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
     }
 
-    /// Returns true if the generic param is a const param.
-    pub fn is_const(&self) -> bool {
-        matches!(
-            &self.tk_prefix,
-            Some(TokenTree::Ident(ident)) if ident == "const"
-        )
+    /// Returns a [`Span`] covering the field, from its name to the end of its type.
+    ///
+    /// Falls back to the span of the name if spans can't be joined.
+    pub fn span(&self) -> Span {
+        let mut tokens = self.name.to_token_stream();
+        self.ty.to_tokens(&mut tokens);
+        joined_span(tokens, self.name.span())
     }
 }
 
-impl<'a> InlineGenericArgs<'a> {
-    /// Returns an owned argument list from this.
-    ///
-    /// # Panics
-    /// If `self` is a mal-formed generic argument list.
-    pub fn to_owned_args(&self) -> GenericArgList {
-        let GenericParamList {
-            tk_l_bracket,
-            params,
-            tk_r_bracket,
-        } = self.0;
-
+impl<'a> Field<'a> {
+    /// Returns the field's type.
+    pub fn ty(&self) -> &'a TyExpr {
+        match self {
+            Field::Named(field) => &field.ty,
+            Field::Tuple(field) => &field.ty,
+        }
+    }
+
+    /// Returns the [`Vec<Attribute>`] of the field.
+    pub fn attributes(&self) -> &'a Vec<Attribute> {
+        match self {
+            Field::Named(field) => &field.attributes,
+            Field::Tuple(field) => &field.attributes,
+        }
+    }
+
+    /// Returns the field's visibility marker, if any.
+    pub fn vis(&self) -> Option<&'a VisMarker> {
+        match self {
+            Field::Named(field) => field.vis_marker.as_ref(),
+            Field::Tuple(field) => field.vis_marker.as_ref(),
+        }
+    }
+
+    /// Returns the field's name, or None for tuple fields.
+    pub fn name(&self) -> Option<&'a Ident> {
+        match self {
+            Field::Named(field) => Some(&field.name),
+            Field::Tuple(_) => None,
+        }
+    }
+}
+
+impl TupleField {
+    /// Create a new field from type, with no attributes or visibility marker.
+    ///
+    /// ```
+    /// # use venial::{TupleField, TyExpr};
+    /// # use quote::quote;
+    /// TupleField::new(TyExpr::from_tokens(quote!(Vec<u8>)))
+    /// # ;
+    /// ```
+    pub fn new(ty: TyExpr) -> Self {
+        TupleField {
+            attributes: Vec::new(),
+            vis_marker: None,
+            ty,
+        }
+    }
+
+    /// Builder method, set the field's [`VisMarker`].
+    pub fn with_vis(mut self, vis_marker: VisMarker) -> Self {
+        self.vis_marker = Some(vis_marker);
+        self
+    }
+
+    /// Builder method, add an [`Attribute`] to the field.
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Returns a [`Span`] covering the field's type.
+    ///
+    /// Falls back to the span of the type's first token if spans can't be joined.
+    pub fn span(&self) -> Span {
+        let fallback = match self.ty.tokens.first() {
+            Some(token) => token.span(),
+            None => Span::call_site(),
+        };
+        joined_span(self.ty.to_token_stream(), fallback)
+    }
+}
+
+impl EnumVariant {
+    /// Create a new variant with no fields, eg `A`.
+    pub fn unit(name: &str) -> Self {
+        EnumVariant {
+            attributes: Vec::new(),
+            vis_marker: None,
+            name: new_ident(name),
+            contents: StructFields::Unit,
+            value: None,
+        }
+    }
+
+    /// Create a new variant with tuple fields, eg `A(u8, u16)`.
+    pub fn tuple(name: &str, fields: Vec<TupleField>) -> Self {
+        let mut tuple_fields = Punctuated::new();
+        for field in fields {
+            tuple_fields.push(field, None);
+        }
+
+        EnumVariant {
+            contents: StructFields::Tuple(TupleStructFields {
+                fields: tuple_fields,
+                tk_parens: GroupSpan {
+                    span: Span::call_site(),
+                    delimiter: Delimiter::Parenthesis,
+                },
+            }),
+            ..EnumVariant::unit(name)
+        }
+    }
+
+    /// Create a new variant with named fields, eg `A { a: u8 }`.
+    pub fn named(name: &str, fields: Vec<NamedField>) -> Self {
+        let mut named_fields = Punctuated::new();
+        for field in fields {
+            named_fields.push(field, None);
+        }
+
+        EnumVariant {
+            contents: StructFields::Named(NamedStructFields {
+                fields: named_fields,
+                tk_braces: GroupSpan {
+                    span: Span::call_site(),
+                    delimiter: Delimiter::Brace,
+                },
+            }),
+            ..EnumVariant::unit(name)
+        }
+    }
+
+    /// Returns true if the variant doesn't store a type.
+    pub fn is_empty_variant(&self) -> bool {
+        matches!(self.contents, StructFields::Unit)
+    }
+
+    /// Returns Some if the variant is a wrapper around a single type.
+    /// Returns None otherwise.
+    pub fn get_single_type(&self) -> Option<&TupleField> {
+        match &self.contents {
+            StructFields::Tuple(fields) if fields.fields.len() == 1 => Some(&fields.fields[0].0),
+            StructFields::Tuple(_fields) => None,
+            StructFields::Unit => None,
+            StructFields::Named(_) => None,
+        }
+    }
+
+    /// Returns the explicit discriminant of the variant, e.g. the `= 5` in `B = 5`.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, Declaration};
+    /// # use quote::quote;
+    /// let enum_decl = parse_declaration(quote!(
+    ///     enum Hello {
+    ///         A,
+    ///         B = 5,
+    ///     }
+    /// )).unwrap();
+    /// let enum_decl = enum_decl.as_enum().unwrap();
+    ///
+    /// assert!(enum_decl.variants[0].0.discriminant().is_none());
+    /// assert_eq!(enum_decl.variants[1].0.discriminant().unwrap().value.to_string(), "5");
+    /// ```
+    pub fn discriminant(&self) -> Option<&EnumVariantValue> {
+        self.value.as_ref()
+    }
+
+    /// Returns an iterator over the names of the variant's fields.
+    ///
+    /// Tuple fields are named after their index, see [`Struct::field_names`].
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let enum_decl = parse_declaration(quote!(
+    ///     enum Hello {
+    ///         A { foo: Foo, bar: Bar },
+    ///         B(Foo, Bar),
+    ///     }
+    /// )).unwrap();
+    /// let enum_decl = enum_decl.as_enum().unwrap();
+    ///
+    /// let field_names: Vec<_> = enum_decl.variants[0].0.field_names().collect();
+    /// assert_eq!(field_names, ["foo", "bar"]);
+    /// let field_names: Vec<_> = enum_decl.variants[1].0.field_names().collect();
+    /// assert_eq!(field_names, ["0", "1"]);
+    /// ```
+    pub fn field_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.contents.field_names()
+    }
+
+    /// Returns an iterator over references to the variant's field types.
+    pub fn field_types(&self) -> impl Iterator<Item = &TyExpr> {
+        self.contents.field_types()
+    }
+
+    /// Returns an iterator over the variant's fields, see [`Struct::fields_iter`].
+    pub fn fields_iter(&self) -> impl Iterator<Item = Field<'_>> {
+        self.contents.fields_iter()
+    }
+}
+
+impl EnumVariantValue {
+    /// Returns the discriminant's value as a token stream, without the `=`.
+    pub fn value_tokens(&self) -> TokenStream {
+        self.value.to_token_stream()
+    }
+
+    /// Returns a [`Span`] covering the discriminant's value expression.
+    ///
+    /// If the value is parenthesized, the span covers the parentheses too.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let enum_decl = parse_declaration(quote!(
+    ///     enum Hello {
+    ///         A = (1 << 4),
+    ///     }
+    /// )).unwrap();
+    /// let value = enum_decl.as_enum().unwrap().variants[0].0.discriminant().unwrap();
+    ///
+    /// assert_eq!(value.tk_equal.as_char(), '=');
+    /// assert_eq!(value.value_tokens().to_string(), "(1 << 4)");
+    /// # let _ = value.span();
+    /// ```
+    pub fn span(&self) -> Span {
+        self.value.span()
+    }
+
+    /// Returns a [`Span`] covering the `=` and the value.
+    ///
+    /// Falls back to the span of the value if spans can't be joined.
+    pub fn full_span(&self) -> Span {
+        self.tk_equal
+            .span()
+            .join(self.span())
+            .unwrap_or_else(|| self.span())
+    }
+}
+
+impl FnQualifiers {
+    /// Whether exactly either `const` or `unsafe` attribute is set, and no other one
+    /// (so the tokens could be the start of a constant or impl declaration)
+    pub(crate) fn has_only_const_xor_unsafe(&self) -> bool {
+        (self.tk_const.is_some() ^ self.tk_unsafe.is_some())
+            && self.tk_default.is_none()
+            && self.tk_async.is_none()
+            && self.tk_extern.is_none()
+            && self.extern_abi.is_none()
+    }
+
+    /// Whether the qualifiers could be the start of an `extern` block
+    /// (`extern`, optionally preceded by `unsafe`, and no other one)
+    pub(crate) fn is_extern_block_prefix(&self) -> bool {
+        self.tk_extern.is_some()
+            && self.tk_default.is_none()
+            && self.tk_const.is_none()
+            && self.tk_async.is_none()
+    }
+}
+
+impl GenericParamList {
+    /// Builder method, add a [`GenericParam`].
+    ///
+    /// Add lifetime params to the beginning of the list.
+    pub fn with_param(mut self, param: GenericParam) -> Self {
+        if param.is_lifetime() {
+            self.params.insert(0, param, None);
+        } else {
+            self.params.push(param, None);
+        }
+        self
+    }
+
+    /// Removes the param named `name` and returns it, if any.
+    ///
+    /// The `'` prefix of lifetimes is ignored, so `remove_param("a")` removes `'a`.
+    pub fn remove_param(&mut self, name: &str) -> Option<GenericParam> {
+        let index = self.params.items().position(|param| param.name == name)?;
+        Some(self.params.remove(index))
+    }
+
+    /// See [`InlineGenericArgs`] for details.
+    pub fn as_inline_args(&self) -> InlineGenericArgs<'_> {
+        InlineGenericArgs(self)
+    }
+
+    /// Returns the params in declaration order, each tagged with its kind.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, GenericParamKind};
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello<'a, T, const N: usize>(&'a [T; N]);
+    /// )).unwrap();
+    /// let kinds: Vec<_> = struct_type
+    ///     .generic_params()
+    ///     .unwrap()
+    ///     .iter_kinded()
+    ///     .map(|(kind, _param)| kind)
+    ///     .collect();
+    /// assert_eq!(
+    ///     kinds,
+    ///     [GenericParamKind::Lifetime, GenericParamKind::Type, GenericParamKind::Const]
+    /// );
+    /// ```
+    pub fn iter_kinded(&self) -> impl Iterator<Item = (GenericParamKind, &GenericParam)> {
+        self.params.items().map(|param| (param.kind(), param))
+    }
+}
+
+/// Collects params into a list, with lifetimes first as [`GenericParamList::with_param`] does.
+///
+/// Params otherwise keep their relative order.
+///
+/// ```
+/// # use venial::{GenericParam, GenericParamList};
+/// # use quote::{quote, ToTokens};
+/// let params: GenericParamList = vec![
+///     GenericParam::ty("T"),
+///     GenericParam::lifetime("a"),
+///     GenericParam::const_param("N", quote!(usize).into_iter().collect()),
+///     GenericParam::lifetime("b"),
+/// ]
+/// .into_iter()
+/// .collect();
+/// assert_eq!(
+///     params.to_token_stream().to_string(),
+///     "< 'a , 'b , T , const N : usize >"
+/// );
+/// ```
+impl FromIterator<GenericParam> for GenericParamList {
+    fn from_iter<I: IntoIterator<Item = GenericParam>>(iter: I) -> Self {
+        let (lifetimes, other_params): (Vec<_>, Vec<_>) =
+            iter.into_iter().partition(GenericParam::is_lifetime);
+
+        let mut list = GenericParamList::default();
+        for param in lifetimes.into_iter().chain(other_params) {
+            list.params.push(param, None);
+        }
+        list
+    }
+}
+
+impl GenericParam {
+    /// Create new lifetime param from name.
+    ///
+    /// ```
+    /// # use venial::GenericParam;
+    /// GenericParam::lifetime("a")
+    /// # ;
+    /// ```
+    pub fn lifetime(name: &str) -> Self {
+        let lifetime_ident = Ident::new(name, Span::call_site());
+        GenericParam {
+            attributes: Vec::new(),
+            tk_prefix: Some(Punct::new('\'', Spacing::Joint).into()),
+            name: lifetime_ident,
+            bound: None,
+            tk_equals: None,
+            default_value: None,
+        }
+    }
+
+    /// Create new lifetime param from name and bound.
+    ///
+    /// ```
+    /// # use venial::GenericParam;
+    /// # use quote::quote;
+    /// GenericParam::bounded_lifetime("a", quote!(b + c).into_iter().collect())
+    /// # ;
+    /// ```
+    pub fn bounded_lifetime(name: &str, bound: Vec<TokenTree>) -> Self {
+        let lifetime_ident = Ident::new(name, Span::call_site());
+        GenericParam {
+            attributes: Vec::new(),
+            tk_prefix: Some(Punct::new('\'', Spacing::Alone).into()),
+            name: lifetime_ident,
+            bound: Some(GenericBound {
+                tk_colon: Punct::new(':', Spacing::Alone),
+                tokens: bound,
+            }),
+            tk_equals: None,
+            default_value: None,
+        }
+    }
+
+    /// Create new type param from name.
+    ///
+    /// ```
+    /// # use venial::GenericParam;
+    /// GenericParam::ty("T")
+    /// # ;
+    /// ```
+    pub fn ty(name: &str) -> Self {
+        let ty_ident = new_ident(name);
+        GenericParam {
+            attributes: Vec::new(),
+            tk_prefix: None,
+            name: ty_ident,
+            bound: None,
+            tk_equals: None,
+            default_value: None,
+        }
+    }
+
+    /// Create new type param from name and bound.
+    ///
+    /// ```
+    /// # use venial::GenericParam;
+    /// # use quote::quote;
+    /// GenericParam::bounded_ty("T", quote!(Debug + Eq).into_iter().collect())
+    /// # ;
+    /// ```
+    pub fn bounded_ty(name: &str, bound: Vec<TokenTree>) -> Self {
+        let ty_ident = new_ident(name);
+        GenericParam {
+            attributes: Vec::new(),
+            tk_prefix: None,
+            name: ty_ident,
+            bound: Some(GenericBound {
+                tk_colon: Punct::new(':', Spacing::Alone),
+                tokens: bound,
+            }),
+            tk_equals: None,
+            default_value: None,
+        }
+    }
+
+    /// Create new const param from name and type.
+    ///
+    /// ```
+    /// # use venial::GenericParam;
+    /// # use quote::quote;
+    /// GenericParam::const_param("N", quote!(i32).into_iter().collect())
+    /// # ;
+    /// ```
+    pub fn const_param(name: &str, ty: Vec<TokenTree>) -> Self {
+        let const_ident = new_ident(name);
+        GenericParam {
+            attributes: Vec::new(),
+            tk_prefix: Some(Ident::new("const", Span::call_site()).into()),
+            name: const_ident,
+            bound: Some(GenericBound {
+                tk_colon: Punct::new(':', Spacing::Alone),
+                tokens: ty,
+            }),
+            tk_equals: None,
+            default_value: None,
+        }
+    }
+
+    /// Create new const param from name, type and default value.
+    ///
+    /// ```
+    /// # use venial::GenericParam;
+    /// # use quote::quote;
+    /// GenericParam::const_param_with_default(
+    ///     "N",
+    ///     quote!(usize).into_iter().collect(),
+    ///     quote!(8).into_iter().collect(),
+    /// )
+    /// # ;
+    /// ```
+    pub fn const_param_with_default(
+        name: &str,
+        ty: Vec<TokenTree>,
+        default_value: Vec<TokenTree>,
+    ) -> Self {
+        GenericParam {
+            tk_equals: Some(Punct::new('=', Spacing::Alone)),
+            default_value: Some(TyExpr {
+                tokens: default_value,
+            }),
+            ..Self::const_param(name, ty)
+        }
+    }
+
+    /// Builder method, add an [`Attribute`] to the param.
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Returns true if the generic param is a lifetime param.
+    pub fn is_lifetime(&self) -> bool {
+        matches!(
+            &self.tk_prefix,
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '\''
+        )
+    }
+
+    /// Returns true if the generic param is a type param.
+    pub fn is_ty(&self) -> bool {
+        self.tk_prefix.is_none()
+    }
+
+    /// Returns true if the generic param is a const param.
+    pub fn is_const(&self) -> bool {
+        matches!(
+            &self.tk_prefix,
+            Some(TokenTree::Ident(ident)) if ident == "const"
+        )
+    }
+
+    /// Returns whether the generic param is a lifetime, type or const param.
+    pub fn kind(&self) -> GenericParamKind {
+        if self.is_lifetime() {
+            GenericParamKind::Lifetime
+        } else if self.is_const() {
+            GenericParamKind::Const
+        } else {
+            GenericParamKind::Type
+        }
+    }
+
+    /// Returns the bare identifier of the param, eg `a` for `'a`.
+    pub fn ident(&self) -> &Ident {
+        &self.name
+    }
+
+    /// Returns the name of the param as written in generic args, eg `'a`, `T` or `N`.
+    ///
+    /// ```
+    /// # use venial::GenericParam;
+    /// # use quote::quote;
+    /// assert_eq!(GenericParam::lifetime("a").display_name(), "'a");
+    /// assert_eq!(GenericParam::ty("T").display_name(), "T");
+    /// assert_eq!(GenericParam::const_param("N", quote!(usize).into_iter().collect()).display_name(), "N");
+    /// ```
+    pub fn display_name(&self) -> String {
+        if self.is_lifetime() {
+            format!("'{}", self.name)
+        } else {
+            self.name.to_string()
+        }
+    }
+
+    /// Returns the param's bounds, split on top-level `+`.
+    ///
+    /// Returns an empty list if the param has no bound. For const params,
+    /// this returns the param's type.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, Declaration};
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello<'a, T: Into<Vec<u8>> + Clone + 'a>(&'a T);
+    /// )).unwrap();
+    /// let param = &struct_type.generic_params().unwrap().params[1].0;
+    /// let bounds: Vec<_> = param.bounds().iter().map(|bound| bound.to_string()).collect();
+    /// assert_eq!(bounds, ["Into < Vec < u8 >>", "Clone", "'a"]);
+    /// ```
+    pub fn bounds(&self) -> Vec<TokenStream> {
+        match &self.bound {
+            Some(bound) => bound.split(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns true if the param has a `?Sized` bound, eg `T: ?Sized`.
+    ///
+    /// Only the param's own bound is checked, not the where clause.
+    pub fn is_maybe_sized(&self) -> bool {
+        match &self.bound {
+            Some(bound) => bound.is_maybe_sized(),
+            None => false,
+        }
+    }
+
+    /// Returns the raw bound of the param, including its colon, eg `: Clone + 'a`.
+    ///
+    /// For const params, this is the param's type, eg `: usize`.
+    pub fn bound(&self) -> Option<&GenericBound> {
+        self.bound.as_ref()
+    }
+
+    /// Replaces the bound of the param and returns the previous one.
+    ///
+    /// ```
+    /// # use venial::{parse_declaration, GenericBound};
+    /// # use proc_macro2::{Punct, Spacing};
+    /// # use quote::{quote, ToTokens};
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello<T: Clone>(T);
+    /// )).unwrap();
+    /// let mut param = struct_type.generic_params().unwrap().params[0].0.clone();
+    /// let previous = param.set_bound(Some(GenericBound {
+    ///     tk_colon: Punct::new(':', Spacing::Alone),
+    ///     tokens: quote!(Debug + Send).into_iter().collect(),
+    /// }));
+    /// assert_eq!(previous.unwrap().to_token_stream().to_string(), ": Clone");
+    /// assert_eq!(param.to_token_stream().to_string(), "T : Debug + Send");
+    /// ```
+    pub fn set_bound(&mut self, bound: Option<GenericBound>) -> Option<GenericBound> {
+        std::mem::replace(&mut self.bound, bound)
+    }
+}
+
+impl GenericBound {
+    /// Returns the individual bounds, split on top-level `+`.
+    ///
+    /// For instance, `: Into<Vec<u8>> + Clone + 'a` returns `Into<Vec<u8>>`, `Clone` and `'a`.
+    pub fn split(&self) -> Vec<TokenStream> {
+        let mut bounds = Vec::new();
+        let mut current = TokenStream::new();
+        let mut bracket_count = 0;
+        let mut prev_token_is_dash = false;
+
+        for token in &self.tokens {
+            match token {
+                TokenTree::Punct(punct) if punct.as_char() == '+' && bracket_count == 0 => {
+                    bounds.push(std::mem::take(&mut current));
+                    prev_token_is_dash = false;
+                    continue;
+                }
+                TokenTree::Punct(punct) if punct.as_char() == '<' => bracket_count += 1,
+                TokenTree::Punct(punct) if punct.as_char() == '>' && !prev_token_is_dash => {
+                    bracket_count -= 1
+                }
+                _ => (),
+            }
+            prev_token_is_dash = matches!(token, TokenTree::Punct(punct) if punct.as_char() == '-');
+            current.extend(Some(token.clone()));
+        }
+
+        if !current.is_empty() {
+            bounds.push(current);
+        }
+        bounds
+    }
+
+    /// Returns the relaxed bounds, without their leading `?`.
+    ///
+    /// For instance, `: Clone + ?Sized` returns `Sized`.
+    pub fn relaxed_bounds(&self) -> Vec<TokenStream> {
+        self.split()
+            .into_iter()
+            .filter_map(|bound| {
+                let mut tokens = bound.into_iter();
+                match tokens.next() {
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == '?' => {
+                        Some(tokens.collect())
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if one of the bounds is `?Sized`, possibly with a path like `?std::marker::Sized`.
+    pub fn is_maybe_sized(&self) -> bool {
+        self.relaxed_bounds().into_iter().any(|bound| {
+            matches!(bound.into_iter().last(), Some(TokenTree::Ident(ident)) if ident == "Sized")
+        })
+    }
+}
+
+impl<'a> InlineGenericArgs<'a> {
+    /// Returns an owned argument list from this.
+    ///
+    /// # Panics
+    /// If `self` is a mal-formed generic argument list.
+    pub fn to_owned_args(&self) -> GenericArgList {
+        let GenericParamList {
+            tk_l_bracket,
+            params,
+            tk_r_bracket,
+        } = self.0;
+
         GenericArgList {
             tk_turbofish_colons: None, // TODO add if GenericParamList supports this, too
             tk_l_bracket: tk_l_bracket.clone(),
@@ -656,70 +2755,443 @@ impl<'a> InlineGenericArgs<'a> {
             tk_r_bracket: tk_r_bracket.clone(),
         }
     }
-}
+}
+
+impl WhereClause {
+    /// Create where-clause with a single item.
+    pub fn from_item(item: WhereClauseItem) -> Self {
+        Self::default().with_item(item)
+    }
+
+    /// Builder method, add an item to the where-clause.
+    pub fn with_item(mut self, item: WhereClauseItem) -> Self {
+        self.items.push(item, None);
+        self
+    }
+
+    /// Return an iterator over the items of the where-clause.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello<T, U>(T, U) where T: Clone, U: Copy;
+    /// )).unwrap();
+    /// let where_clause = struct_type.as_struct().unwrap().where_clause.as_ref().unwrap();
+    /// assert_eq!(where_clause.items().count(), 2);
+    /// ```
+    pub fn items(&self) -> impl Iterator<Item = &WhereClauseItem> {
+        self.items.items()
+    }
+
+    /// Return an iterator that mutates the items of the where-clause.
+    pub fn items_mut(&mut self) -> impl Iterator<Item = &mut WhereClauseItem> {
+        self.items.items_mut()
+    }
+}
+
+impl WhereClauseItem {
+    /// Helper method to create a WhereClauseItem from a quote.
+    ///
+    /// # Panics
+    ///
+    /// Panics if given a token stream that isn't a valid where-clause item.
+    /// See [`WhereClauseItem::try_parse`] for a non-panicking version.
+    pub fn parse(tokens: TokenStream) -> Self {
+        Self::try_parse(tokens).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a WhereClauseItem from a token stream, eg `T: Clone + 'a`.
+    ///
+    /// ```
+    /// # use venial::WhereClauseItem;
+    /// # use quote::quote;
+    /// assert!(WhereClauseItem::try_parse(quote!(T: Clone)).is_ok());
+    /// assert!(WhereClauseItem::try_parse(quote!(T Clone)).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns error if given a token stream that isn't a valid where-clause item.
+    pub fn try_parse(tokens: TokenStream) -> Result<Self, Error> {
+        let mut tokens = tokens.into_iter().peekable();
+
+        let left_side = crate::parse_type::consume_where_item_left_side(&mut tokens);
+
+        let colon = match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => punct,
+            Some(token) => {
+                return Err(Error::new_at_span(
+                    token.span(),
+                    format!(
+                        "cannot parse where-clause item: expected ':', found token {:?}",
+                        token
+                    ),
+                ))
+            }
+            None => {
+                return Err(Error::new(
+                    "cannot parse where-clause item: expected colon, found end of stream",
+                ))
+            }
+        };
+
+        let bound_tokens = tokens.collect();
+
+        Ok(WhereClauseItem {
+            left_side,
+            bound: GenericBound {
+                tk_colon: colon,
+                tokens: bound_tokens,
+            },
+        })
+    }
+
+    /// Returns the lifetime bounds of the item, eg `'a` in `T: 'a + Debug`.
+    pub fn lifetime_bounds(&self) -> Vec<TokenStream> {
+        self.bound
+            .split()
+            .into_iter()
+            .filter(is_lifetime_bound)
+            .collect()
+    }
+
+    /// Returns the trait bounds of the item, eg `Debug` in `T: 'a + Debug`.
+    ///
+    /// ```
+    /// # use venial::WhereClauseItem;
+    /// # use quote::quote;
+    /// let item = WhereClauseItem::parse(quote!(T: 'a + Debug + Into<u8>));
+    /// let trait_bounds: Vec<_> = item.trait_bounds().iter().map(|b| b.to_string()).collect();
+    /// assert_eq!(trait_bounds, ["Debug", "Into < u8 >"]);
+    /// ```
+    pub fn trait_bounds(&self) -> Vec<TokenStream> {
+        self.bound
+            .split()
+            .into_iter()
+            .filter(|bound| !is_lifetime_bound(bound))
+            .collect()
+    }
+}
+
+impl std::str::FromStr for WhereClauseItem {
+    type Err = Error;
+
+    /// Lexes and parses a where-clause item, eg `"T: Clone + 'a"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens: TokenStream = s
+            .parse()
+            .map_err(|error| Error::new(format!("cannot parse where-clause item: {}", error)))?;
+        Self::try_parse(tokens)
+    }
+}
+
+fn is_lifetime_bound(bound: &TokenStream) -> bool {
+    matches!(
+        bound.clone().into_iter().next(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '\''
+    )
+}
+
+impl TyExpr {
+    /// Create a type expression from a token stream.
+    pub fn from_tokens(tokens: TokenStream) -> Self {
+        TyExpr {
+            tokens: tokens.into_iter().collect(),
+        }
+    }
+
+    /// Tries to parse this type as a [`Path`] such as `path::to::Type<'a, other::Type>`.
+    ///
+    /// If it does not match a path, `None` is returned.
+    pub fn as_path(&self) -> Option<Path> {
+        let tokens = tokens_from_slice(&self.tokens);
+
+        consume_path(tokens)
+    }
+
+    /// Returns the inner type if this type is an `Option<T>`, eg `T`.
+    ///
+    /// Recognizes `Option<T>`, `std::option::Option<T>` and `core::option::Option<T>`,
+    /// with or without leading `::` or turbofish.
+    ///
+    /// ```
+    /// # use venial::TyExpr;
+    /// # use quote::{quote, ToTokens};
+    /// let ty = TyExpr {
+    ///     tokens: quote!(std::option::Option<Vec<u8>>).into_iter().collect(),
+    /// };
+    ///
+    /// assert_eq!(ty.option_inner().unwrap().to_token_stream().to_string(), "Vec < u8 >");
+    /// ```
+    pub fn option_inner(&self) -> Option<TyExpr> {
+        let path = self.as_path()?;
+        if !is_std_path(&path, "option", "Option") {
+            return None;
+        }
+
+        let generic_args = path.segments.last()?.generic_args.as_ref()?;
+        match generic_args.args.items().collect::<Vec<_>>().as_slice() {
+            [GenericArg::TyOrConst { expr }] => Some(expr.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the type is a `PhantomData`, with any generic argument.
+    ///
+    /// Recognizes `PhantomData`, `std::marker::PhantomData` and `core::marker::PhantomData`.
+    ///
+    /// ```
+    /// # use venial::TyExpr;
+    /// # use quote::quote;
+    /// let ty = TyExpr {
+    ///     tokens: quote!(std::marker::PhantomData<fn() -> T>).into_iter().collect(),
+    /// };
+    ///
+    /// assert!(ty.is_phantom_data());
+    /// ```
+    pub fn is_phantom_data(&self) -> bool {
+        match self.as_path() {
+            Some(path) => is_std_path(&path, "marker", "PhantomData"),
+            None => false,
+        }
+    }
+
+    /// Returns true if the type is a reference, such as `&T`, `&'a T` or `&mut T`.
+    pub fn is_reference(&self) -> bool {
+        self.reference_parts().is_some()
+    }
+
+    /// Returns true if the type is a mutable reference, such as `&mut T` or `&'a mut T`.
+    pub fn is_mut_reference(&self) -> bool {
+        matches!(self.reference_parts(), Some((_, true, _)))
+    }
+
+    /// Returns the lifetime of a reference type, eg `a` for `&'a T`.
+    ///
+    /// Returns None if the type isn't a reference or if the lifetime is elided.
+    pub fn reference_lifetime(&self) -> Option<Ident> {
+        self.reference_parts()?.0
+    }
+
+    /// Returns the referent of a reference type, eg `T` for `&'a mut T`.
+    ///
+    /// Returns None if the type isn't a reference.
+    ///
+    /// ```
+    /// # use venial::TyExpr;
+    /// # use quote::{quote, ToTokens};
+    /// let ty = TyExpr {
+    ///     tokens: quote!(&'a mut Vec<T>).into_iter().collect(),
+    /// };
+    ///
+    /// assert_eq!(ty.strip_reference().unwrap().to_token_stream().to_string(), "Vec < T >");
+    /// ```
+    pub fn strip_reference(&self) -> Option<TyExpr> {
+        let (_, _, referent) = self.reference_parts()?;
+        Some(TyExpr {
+            tokens: referent.to_vec(),
+        })
+    }
 
-impl WhereClause {
-    /// Create where-clause with a single item.
-    pub fn from_item(item: WhereClauseItem) -> Self {
-        Self::default().with_item(item)
+    /// Returns true if the type is a trait object, such as `dyn Display + Send`.
+    ///
+    /// References aren't looked through: for `&dyn Error`, use
+    /// [`TyExpr::strip_reference`] first.
+    ///
+    /// ```
+    /// # use venial::TyExpr;
+    /// # use quote::quote;
+    /// let ty = TyExpr::from_tokens(quote!(&dyn std::error::Error));
+    ///
+    /// assert!(!ty.is_dyn());
+    /// assert!(ty.strip_reference().unwrap().is_dyn());
+    /// ```
+    pub fn is_dyn(&self) -> bool {
+        self.bounds_after_keyword("dyn").is_some()
     }
 
-    /// Builder method, add an item to the where-clause.
-    pub fn with_item(mut self, item: WhereClauseItem) -> Self {
-        self.items.push(item, None);
-        self
+    /// Returns true if the type is an `impl Trait` type, such as `impl Iterator<Item = u8>`.
+    pub fn is_impl_trait(&self) -> bool {
+        self.bounds_after_keyword("impl").is_some()
     }
-}
 
-impl WhereClauseItem {
-    /// Helper method to create a WhereClauseItem from a quote.
+    /// Returns the bounds of a `dyn` or `impl` type, eg `Display + Send` for `dyn Display + Send`.
     ///
-    /// # Panics
+    /// Returns None if the type is neither a trait object nor an `impl Trait` type.
     ///
-    /// Panics if given a token stream that isn't a valid where-clause item.
-    pub fn parse(tokens: TokenStream) -> Self {
-        let mut tokens = tokens.into_iter().peekable();
+    /// ```
+    /// # use venial::TyExpr;
+    /// # use quote::quote;
+    /// let ty = TyExpr::from_tokens(quote!(impl Iterator<Item = u8> + 'a));
+    ///
+    /// assert_eq!(ty.trait_bounds().unwrap().to_string(), "Iterator < Item = u8 > + 'a");
+    /// ```
+    pub fn trait_bounds(&self) -> Option<TokenStream> {
+        let bounds = self
+            .bounds_after_keyword("dyn")
+            .or_else(|| self.bounds_after_keyword("impl"))?;
+        Some(TokenStream::from_iter(bounds.iter().cloned()))
+    }
 
-        let left_side = crate::parse_utils::consume_stuff_until(
-            &mut tokens,
-            |token| match token {
-                TokenTree::Punct(punct) if punct.as_char() == ':' => true,
-                _ => false,
-            },
-            false,
-        );
+    /// Returns the element types if this type is a tuple, eg `A` and `B<C, D>` for `(A, B<C, D>)`.
+    ///
+    /// The unit type `()` returns an empty list. A parenthesized type without
+    /// a comma, eg `(A)`, isn't a tuple and returns None.
+    ///
+    /// ```
+    /// # use venial::TyExpr;
+    /// # use quote::{quote, ToTokens};
+    /// let ty = TyExpr::from_tokens(quote!((u8, HashMap<K, V>)));
+    /// let elements = ty.as_tuple().unwrap();
+    ///
+    /// assert_eq!(elements.len(), 2);
+    /// assert_eq!(elements[1].to_token_stream().to_string(), "HashMap < K , V >");
+    /// ```
+    pub fn as_tuple(&self) -> Option<Vec<TyExpr>> {
+        let group = match self.tokens.as_slice() {
+            [TokenTree::Group(group)] if group.delimiter() == Delimiter::Parenthesis => group,
+            _ => return None,
+        };
 
-        let colon = match tokens.next() {
-            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => punct,
-            Some(token) => panic!(
-                "cannot parse where-clause item: expected ':', found token {:?}",
-                token
-            ),
-            None => {
-                panic!("cannot parse where-clause item: expected colon, found end of stream")
+        let mut tokens = group.stream().into_iter().peekable();
+        let mut elements = Vec::new();
+        let mut has_comma = false;
+        while tokens.peek().is_some() {
+            let element = consume_stuff_until(&mut tokens, |token| match token {
+                TokenTree::Punct(punct) => punct.as_char() == ',',
+                _ => false,
+            });
+            // Stuck on an unbalanced `>`, or an empty element like `(A,,)`
+            if element.is_empty() {
+                return None;
             }
+            elements.push(TyExpr { tokens: element });
+            has_comma |= consume_punct(&mut tokens, ',').is_some();
+        }
+
+        if elements.len() == 1 && !has_comma {
+            return None;
+        }
+        Some(elements)
+    }
+
+    /// Returns the element type and length if this type is an array, eg `T` and `N` for `[T; N]`.
+    ///
+    /// Slices such as `[T]` return None.
+    ///
+    /// ```
+    /// # use venial::TyExpr;
+    /// # use quote::{quote, ToTokens};
+    /// let ty = TyExpr::from_tokens(quote!([Option<u8>; 4 * SIZE]));
+    /// let (element, len) = ty.as_array().unwrap();
+    ///
+    /// assert_eq!(element.to_token_stream().to_string(), "Option < u8 >");
+    /// assert_eq!(len.to_string(), "4 * SIZE");
+    /// ```
+    pub fn as_array(&self) -> Option<(TyExpr, TokenStream)> {
+        let group = match self.tokens.as_slice() {
+            [TokenTree::Group(group)] if group.delimiter() == Delimiter::Bracket => group,
+            _ => return None,
         };
 
-        let bound_tokens = tokens.collect();
+        let mut tokens = group.stream().into_iter().peekable();
+        let element = consume_stuff_until(&mut tokens, |token| match token {
+            TokenTree::Punct(punct) => punct.as_char() == ';',
+            _ => false,
+        });
+        consume_punct(&mut tokens, ';')?;
 
-        WhereClauseItem {
-            left_side,
-            bound: GenericBound {
-                tk_colon: colon,
-                tokens: bound_tokens,
-            },
+        Some((TyExpr { tokens: element }, tokens.collect()))
+    }
+
+    /// Returns the tokens following `keyword` if the type starts with it.
+    fn bounds_after_keyword(&self, keyword: &str) -> Option<&[TokenTree]> {
+        match self.tokens.as_slice() {
+            [TokenTree::Ident(ident), rest @ ..] if ident == keyword => Some(rest),
+            _ => None,
         }
     }
+
+    /// Splits a reference type into its lifetime, mutability and referent tokens.
+    fn reference_parts(&self) -> Option<(Option<Ident>, bool, &[TokenTree])> {
+        let mut tokens = match self.tokens.as_slice() {
+            [TokenTree::Punct(punct), rest @ ..] if punct.as_char() == '&' => rest,
+            _ => return None,
+        };
+
+        let lifetime = match tokens {
+            [TokenTree::Punct(punct), TokenTree::Ident(ident), rest @ ..]
+                if punct.as_char() == '\'' =>
+            {
+                tokens = rest;
+                Some(ident.clone())
+            }
+            _ => None,
+        };
+
+        let is_mut = match tokens {
+            [TokenTree::Ident(ident), rest @ ..] if ident == "mut" => {
+                tokens = rest;
+                true
+            }
+            _ => false,
+        };
+
+        Some((lifetime, is_mut, tokens))
+    }
 }
 
-impl TyExpr {
-    /// Tries to parse this type as a [`Path`] such as `path::to::Type<'a, other::Type>`.
+impl VisMarker {
+    /// Create a plain `pub` marker.
+    pub fn pub_vis() -> Self {
+        VisMarker {
+            tk_token1: Ident::new("pub", Span::call_site()).into(),
+            tk_token2: None,
+        }
+    }
+
+    /// Returns true if the marker is a plain `pub`, with no restriction.
+    pub fn is_pub(&self) -> bool {
+        matches!(&self.tk_token1, TokenTree::Ident(ident) if ident == "pub")
+            && self.tk_token2.is_none()
+    }
+
+    /// Returns true if the marker is `pub(crate)` or `crate`.
+    pub fn is_crate(&self) -> bool {
+        match &self.tk_token2 {
+            Some(TokenTree::Group(group)) => {
+                let tokens: Vec<_> = group.stream().into_iter().collect();
+                matches!(tokens.as_slice(), [TokenTree::Ident(ident)] if ident == "crate")
+            }
+            Some(_) => false,
+            None => matches!(&self.tk_token1, TokenTree::Ident(ident) if ident == "crate"),
+        }
+    }
+
+    /// Returns the path of a `pub(in path)` marker, eg `crate::foo` in `pub(in crate::foo)`.
     ///
-    /// If it does not match a path, `None` is returned.
-    pub fn as_path(&self) -> Option<Path> {
-        let tokens = tokens_from_slice(&self.tokens);
+    /// ```
+    /// # use venial::{parse_declaration, Declaration};
+    /// # use quote::quote;
+    /// let struct_decl = parse_declaration(quote!(
+    ///     pub(in crate::foo) struct Hello;
+    /// )).unwrap();
+    /// let vis_marker = struct_decl.as_struct().unwrap().vis_marker.as_ref().unwrap();
+    ///
+    /// assert_eq!(vis_marker.restricted_path().unwrap().to_string(), "crate :: foo");
+    /// ```
+    pub fn restricted_path(&self) -> Option<TokenStream> {
+        let group = match &self.tk_token2 {
+            Some(TokenTree::Group(group)) => group,
+            _ => return None,
+        };
 
-        consume_path(tokens)
+        let mut tokens = group.stream().into_iter();
+        match tokens.next() {
+            Some(TokenTree::Ident(ident)) if ident == "in" => Some(tokens.collect()),
+            _ => None,
+        }
     }
 }
 
@@ -731,4 +3203,611 @@ impl GroupSpan {
             delimiter: group.delimiter(),
         }
     }
+
+    /// Create a proc_macro2 Group around `tokens`, with the stored delimiter and span.
+    ///
+    /// ```
+    /// # use venial::parse_declaration;
+    /// # use quote::quote;
+    /// let struct_type = parse_declaration(quote!(
+    ///     struct Hello {
+    ///         a: i32,
+    ///     }
+    /// )).unwrap();
+    /// let fields = match &struct_type.as_struct().unwrap().fields {
+    ///     venial::StructFields::Named(fields) => fields,
+    ///     _ => unreachable!(),
+    /// };
+    /// let group = fields.tk_braces.clone().into_group(quote!(b: bool,));
+    /// assert_eq!(group.to_string(), "{ b : bool , }");
+    /// ```
+    pub fn into_group(self, tokens: TokenStream) -> Group {
+        let mut group = Group::new(self.delimiter, tokens);
+        group.set_span(self.span);
+        group
+    }
+}
+
+/// Returns true if `path` is `name`, `std::module::name` or `core::module::name`,
+/// with generic args on the last segment only.
+fn is_std_path(path: &Path, module: &str, name: &str) -> bool {
+    let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let is_match = match segments.as_slice() {
+        [ident] => ident == name,
+        [krate, module_ident, ident] => {
+            (krate == "std" || krate == "core") && module_ident == module && ident == name
+        }
+        _ => false,
+    };
+
+    is_match
+        && path.segments[..path.segments.len() - 1]
+            .iter()
+            .all(|s| s.generic_args.is_none())
+}
+
+/// Creates an identifier at the call site, as a raw identifier if `name` starts with `r#`.
+fn new_ident(name: &str) -> Ident {
+    if !name.starts_with("r#") {
+        return Ident::new(name, Span::call_site());
+    }
+
+    // Ident::new rejects raw identifiers, so they go through the lexer instead
+    let mut tokens = name
+        .parse::<TokenStream>()
+        .unwrap_or_else(|_| panic!("`{}` is not a valid identifier", name))
+        .into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(ident)), None) => ident,
+        _ => panic!("`{}` is not a valid identifier", name),
+    }
+}
+
+/// Returns a span from the first to the last token, or `fallback` if spans can't be joined.
+fn joined_span(tokens: TokenStream, fallback: Span) -> Span {
+    let mut tokens = tokens.into_iter();
+    let first = match tokens.next() {
+        Some(token) => token.span(),
+        None => return fallback,
+    };
+    let last = tokens.last().map_or(first, |token| token.span());
+
+    first.join(last).unwrap_or(fallback)
+}
+
+/// Pushes the identifiers in type position within `tokens` to `idents`.
+fn collect_type_idents(tokens: TokenStream, idents: &mut Vec<Ident>) {
+    let mut tokens = tokens.into_iter().peekable();
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Group(group)) => collect_type_idents(group.stream(), idents),
+            // Lifetime, skip its name
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '\'' => {
+                tokens.next();
+            }
+            Some(TokenTree::Ident(ident)) => {
+                let is_keyword = matches!(
+                    ident.to_string().as_str(),
+                    "as" | "const" | "dyn" | "extern" | "fn" | "for" | "impl" | "mut" | "unsafe"
+                );
+                // Path prefix `a::`, or associated type binding `Item =`
+                let is_prefix = matches!(
+                    tokens.peek(),
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == ':'
+                        || (punct.as_char() == '=' && punct.spacing() == Spacing::Alone)
+                );
+                if !is_keyword && !is_prefix {
+                    idents.push(ident);
+                }
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+}
+
+/// Pushes the lifetimes of the signature of `declaration` to `lifetimes`.
+///
+/// See [`Declaration::used_lifetimes`].
+fn collect_declaration_lifetimes(declaration: &Declaration, lifetimes: &mut Vec<Ident>) {
+    if let Some(generic_params) = declaration.generic_params() {
+        for param in generic_params.params.items() {
+            if param.is_lifetime() {
+                push_lifetime(param.name.clone(), &[], lifetimes);
+            }
+            if let Some(bound) = &param.bound {
+                collect_lifetimes(bound.tokens.iter().cloned(), lifetimes);
+            }
+            if let Some(default_value) = &param.default_value {
+                collect_lifetimes(default_value.tokens.iter().cloned(), lifetimes);
+            }
+        }
+    }
+
+    match declaration {
+        Declaration::Struct(struct_decl) => {
+            collect_where_clause_lifetimes(&struct_decl.where_clause, lifetimes);
+            type_walk::fields(&struct_decl.fields, &mut |ty| {
+                collect_lifetimes(ty.tokens.iter().cloned(), lifetimes)
+            });
+        }
+        Declaration::Enum(enum_decl) => {
+            collect_where_clause_lifetimes(&enum_decl.where_clause, lifetimes);
+            for variant in enum_decl.variants.items() {
+                type_walk::fields(&variant.contents, &mut |ty| {
+                    collect_lifetimes(ty.tokens.iter().cloned(), lifetimes)
+                });
+            }
+        }
+        Declaration::Union(union_decl) => {
+            collect_where_clause_lifetimes(&union_decl.where_clause, lifetimes);
+            for field in union_decl.fields.fields.items() {
+                collect_lifetimes(field.ty.tokens.iter().cloned(), lifetimes);
+            }
+        }
+        Declaration::Trait(trait_decl) => {
+            if let Some(bound) = &trait_decl.bound {
+                collect_lifetimes(bound.tokens.iter().cloned(), lifetimes);
+            }
+            collect_where_clause_lifetimes(&trait_decl.where_clause, lifetimes);
+        }
+        Declaration::Impl(impl_decl) => {
+            if let Some(trait_ty) = &impl_decl.trait_ty {
+                collect_lifetimes(trait_ty.tokens.iter().cloned(), lifetimes);
+            }
+            collect_lifetimes(impl_decl.self_ty.tokens.iter().cloned(), lifetimes);
+            collect_where_clause_lifetimes(&impl_decl.where_clause, lifetimes);
+        }
+        Declaration::TyDefinition(ty_decl) => {
+            if let Some(bound) = &ty_decl.bound {
+                collect_lifetimes(bound.tokens.iter().cloned(), lifetimes);
+            }
+            collect_where_clause_lifetimes(&ty_decl.where_clause, lifetimes);
+            if let Some(initializer_ty) = &ty_decl.initializer_ty {
+                collect_lifetimes(initializer_ty.tokens.iter().cloned(), lifetimes);
+            }
+            collect_where_clause_lifetimes(&ty_decl.trailing_where_clause, lifetimes);
+        }
+        Declaration::Function(function) => {
+            type_walk::function_signature(function, &mut |ty| {
+                collect_lifetimes(ty.tokens.iter().cloned(), lifetimes)
+            });
+            collect_where_clause_lifetimes(&function.where_clause, lifetimes);
+        }
+        Declaration::Constant(constant) => {
+            collect_lifetimes(constant.ty.tokens.iter().cloned(), lifetimes);
+        }
+        Declaration::Module(_)
+        | Declaration::ExternBlock(_)
+        | Declaration::Use(_)
+        | Declaration::MacroDef(_) => {}
+    }
+}
+
+fn collect_where_clause_lifetimes(where_clause: &Option<WhereClause>, lifetimes: &mut Vec<Ident>) {
+    for item in where_clause.iter().flat_map(WhereClause::items) {
+        // A `for<...>` on the left side also binds lifetimes in the bound
+        collect_lifetimes(item.to_token_stream(), lifetimes);
+    }
+}
+
+/// Pushes the names of the named lifetimes within `tokens` to `lifetimes`,
+/// skipping the ones already present.
+fn collect_lifetimes(tokens: impl IntoIterator<Item = TokenTree>, lifetimes: &mut Vec<Ident>) {
+    collect_unbound_lifetimes(tokens, &mut Vec::new(), lifetimes);
+}
+
+/// Same as [`collect_lifetimes`], but skips the lifetimes in `bound_lifetimes`.
+///
+/// A `for<...>` adds its lifetimes to `bound_lifetimes` until the end of the
+/// token group it is in.
+fn collect_unbound_lifetimes(
+    tokens: impl IntoIterator<Item = TokenTree>,
+    bound_lifetimes: &mut Vec<Ident>,
+    lifetimes: &mut Vec<Ident>,
+) {
+    let outer_bound_count = bound_lifetimes.len();
+    let mut tokens = tokens.into_iter().peekable();
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Group(group)) => {
+                collect_unbound_lifetimes(group.stream(), bound_lifetimes, lifetimes)
+            }
+            Some(TokenTree::Ident(ident))
+                if ident == "for"
+                    && matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '<') =>
+            {
+                loop {
+                    match tokens.next() {
+                        Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => break,
+                        Some(TokenTree::Ident(name)) => bound_lifetimes.push(name),
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+            }
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '\'' => {
+                if let Some(TokenTree::Ident(name)) = tokens.next() {
+                    push_lifetime(name, bound_lifetimes, lifetimes);
+                }
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    bound_lifetimes.truncate(outer_bound_count);
+}
+
+/// Pushes `name` to `lifetimes` unless it is already there, bound, or special.
+fn push_lifetime(name: Ident, bound_lifetimes: &[Ident], lifetimes: &mut Vec<Ident>) {
+    let is_special = name == "_" || name == "static";
+    if !is_special && !bound_lifetimes.contains(&name) && !lifetimes.contains(&name) {
+        lifetimes.push(name);
+    }
+}
+
+/// Generates the traversal behind [`Declaration::walk_types`] and [`Declaration::map_types`].
+///
+/// Both visit the same positions in the same order, and only differ in whether
+/// they borrow the declaration mutably. `$visit_tokens` passes raw tokens, such
+/// as bounds, as a [`TyExpr`].
+macro_rules! implement_type_traversal {
+    ($module:ident, $items:ident, $visit_tokens:ident, $($ref:tt)+) => {
+        mod $module {
+            use super::*;
+
+            type Visitor<'f> = &'f mut dyn FnMut($($ref)+ TyExpr);
+
+            pub(super) fn declaration(declaration: $($ref)+ Declaration, f: Visitor) {
+                match declaration {
+                    Declaration::Struct(struct_decl) => {
+                        generic_params($($ref)+ struct_decl.generic_params, f);
+                        // The where clause of a tuple struct comes after its fields
+                        if let StructFields::Tuple(_) = struct_decl.fields {
+                            fields($($ref)+ struct_decl.fields, f);
+                            where_clause($($ref)+ struct_decl.where_clause, f);
+                        } else {
+                            where_clause($($ref)+ struct_decl.where_clause, f);
+                            fields($($ref)+ struct_decl.fields, f);
+                        }
+                    }
+                    Declaration::Enum(enum_decl) => {
+                        generic_params($($ref)+ enum_decl.generic_params, f);
+                        where_clause($($ref)+ enum_decl.where_clause, f);
+                        for variant in enum_decl.variants.$items() {
+                            fields($($ref)+ variant.contents, f);
+                        }
+                    }
+                    Declaration::Union(union_decl) => {
+                        generic_params($($ref)+ union_decl.generic_params, f);
+                        where_clause($($ref)+ union_decl.where_clause, f);
+                        for field in union_decl.fields.fields.$items() {
+                            f($($ref)+ field.ty);
+                        }
+                    }
+                    Declaration::Module(mod_decl) => {
+                        for member in $($ref)+ mod_decl.members {
+                            self::declaration(member, f);
+                        }
+                    }
+                    Declaration::Trait(trait_decl) => {
+                        generic_params($($ref)+ trait_decl.generic_params, f);
+                        if let Some(bound) = $($ref)+ trait_decl.bound {
+                            generic_bound(bound, f);
+                        }
+                        where_clause($($ref)+ trait_decl.where_clause, f);
+                        for member in $($ref)+ trait_decl.body_items {
+                            match member {
+                                TraitMember::Method(method) => function(method, f),
+                                TraitMember::Constant(constant) => f($($ref)+ constant.ty),
+                                TraitMember::AssocTy(ty_decl) => ty_definition(ty_decl, f),
+                            }
+                        }
+                    }
+                    Declaration::Impl(impl_decl) => {
+                        generic_params($($ref)+ impl_decl.impl_generic_params, f);
+                        if let Some(trait_ty) = $($ref)+ impl_decl.trait_ty {
+                            f(trait_ty);
+                        }
+                        f($($ref)+ impl_decl.self_ty);
+                        where_clause($($ref)+ impl_decl.where_clause, f);
+                        for member in $($ref)+ impl_decl.body_items {
+                            match member {
+                                ImplMember::Method(method) => function(method, f),
+                                ImplMember::Constant(constant) => f($($ref)+ constant.ty),
+                                ImplMember::AssocTy(ty_decl) => ty_definition(ty_decl, f),
+                            }
+                        }
+                    }
+                    Declaration::ExternBlock(extern_decl) => {
+                        for member in $($ref)+ extern_decl.body_items {
+                            match member {
+                                ExternMember::Function(method) => function(method, f),
+                                ExternMember::Static(constant) => f($($ref)+ constant.ty),
+                                ExternMember::Ty(ty_decl) => ty_definition(ty_decl, f),
+                            }
+                        }
+                    }
+                    Declaration::TyDefinition(ty_decl) => ty_definition(ty_decl, f),
+                    Declaration::Function(method) => function(method, f),
+                    Declaration::Constant(constant) => f($($ref)+ constant.ty),
+                    Declaration::Use(_) => {}
+                    Declaration::MacroDef(_) => {}
+                }
+            }
+
+            pub(super) fn fields(fields: $($ref)+ StructFields, f: Visitor) {
+                match fields {
+                    StructFields::Unit => {}
+                    StructFields::Tuple(fields) => {
+                        for field in fields.fields.$items() {
+                            f($($ref)+ field.ty);
+                        }
+                    }
+                    StructFields::Named(fields) => {
+                        for field in fields.fields.$items() {
+                            f($($ref)+ field.ty);
+                        }
+                    }
+                }
+            }
+
+            pub(super) fn function_signature(function: $($ref)+ Function, f: Visitor) {
+                for param in function.params.$items() {
+                    if let FnParam::Typed(param) = param {
+                        f($($ref)+ param.ty);
+                    }
+                }
+                if let Some(return_ty) = $($ref)+ function.return_ty {
+                    f(return_ty);
+                }
+            }
+
+            fn function(function: $($ref)+ Function, f: Visitor) {
+                generic_params($($ref)+ function.generic_params, f);
+                function_signature(function, f);
+                where_clause($($ref)+ function.where_clause, f);
+            }
+
+            fn ty_definition(ty_decl: $($ref)+ TyDefinition, f: Visitor) {
+                generic_params($($ref)+ ty_decl.generic_params, f);
+                if let Some(bound) = $($ref)+ ty_decl.bound {
+                    generic_bound(bound, f);
+                }
+                where_clause($($ref)+ ty_decl.where_clause, f);
+                if let Some(initializer_ty) = $($ref)+ ty_decl.initializer_ty {
+                    f(initializer_ty);
+                }
+                where_clause($($ref)+ ty_decl.trailing_where_clause, f);
+            }
+
+            fn generic_params(generic_params: $($ref)+ Option<GenericParamList>, f: Visitor) {
+                if let Some(generic_params) = generic_params {
+                    for param in generic_params.params.$items() {
+                        if param.is_lifetime() {
+                            continue;
+                        }
+                        // The bound of a const param is its type
+                        if let Some(bound) = $($ref)+ param.bound {
+                            generic_bound(bound, f);
+                        }
+                        // The default of a const param is an expression
+                        if param.is_const() {
+                            continue;
+                        }
+                        if let Some(default_value) = $($ref)+ param.default_value {
+                            f(default_value);
+                        }
+                    }
+                }
+            }
+
+            fn where_clause(where_clause: $($ref)+ Option<WhereClause>, f: Visitor) {
+                if let Some(where_clause) = where_clause {
+                    for item in where_clause.items.$items() {
+                        if is_lifetime_only_bound(&item.left_side) {
+                            continue;
+                        }
+                        $visit_tokens($($ref)+ item.left_side, f);
+                        generic_bound($($ref)+ item.bound, f);
+                    }
+                }
+            }
+
+            fn generic_bound(bound: $($ref)+ GenericBound, f: Visitor) {
+                if !is_lifetime_only_bound(&bound.tokens) {
+                    $visit_tokens($($ref)+ bound.tokens, f);
+                }
+            }
+        }
+    };
+}
+
+implement_type_traversal! { type_walk, items, walk_tokens_as_type, & }
+implement_type_traversal! { type_map, items_mut, map_tokens_as_type, &mut }
+
+/// Passes a copy of `tokens` to `f` as a [`TyExpr`].
+fn walk_tokens_as_type(tokens: &[TokenTree], f: &mut dyn FnMut(&TyExpr)) {
+    f(&TyExpr {
+        tokens: tokens.to_vec(),
+    });
+}
+
+/// Passes `tokens` to `f` as a [`TyExpr`], then stores the rewritten tokens back.
+fn map_tokens_as_type(tokens: &mut Vec<TokenTree>, f: &mut dyn FnMut(&mut TyExpr)) {
+    let mut ty = TyExpr {
+        tokens: std::mem::take(tokens),
+    };
+    f(&mut ty);
+    *tokens = ty.tokens;
+}
+
+/// Returns true if `tokens` hold nothing but lifetimes, eg `'a + 'b`.
+fn is_lifetime_only_bound(tokens: &[TokenTree]) -> bool {
+    let mut tokens = tokens.iter();
+    loop {
+        match tokens.next() {
+            None => return true,
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '\'' => match tokens.next() {
+                Some(TokenTree::Ident(_)) => {}
+                _ => return false,
+            },
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '+' => {}
+            Some(_) => return false,
+        }
+    }
+}
+
+fn walk_declaration_attributes(declaration: &Declaration, f: &mut dyn FnMut(&Attribute)) {
+    walk_attribute_list(declaration.attributes(), f);
+    walk_generic_params_attributes(declaration.generic_params(), f);
+    match declaration {
+        Declaration::Struct(struct_decl) => walk_fields_attributes(&struct_decl.fields, f),
+        Declaration::Enum(enum_decl) => {
+            for variant in enum_decl.variants.items() {
+                walk_attribute_list(&variant.attributes, f);
+                walk_fields_attributes(&variant.contents, f);
+            }
+        }
+        Declaration::Union(union_decl) => {
+            for field in union_decl.fields.fields.items() {
+                walk_attribute_list(&field.attributes, f);
+            }
+        }
+        Declaration::Module(mod_decl) => {
+            walk_attribute_list(&mod_decl.inner_attributes, f);
+            for member in &mod_decl.members {
+                walk_declaration_attributes(member, f);
+            }
+        }
+        Declaration::Trait(trait_decl) => {
+            walk_attribute_list(&trait_decl.inner_attributes, f);
+            for member in &trait_decl.body_items {
+                match member {
+                    TraitMember::Method(function) => walk_function_attributes(function, f),
+                    TraitMember::Constant(constant) => walk_attribute_list(&constant.attributes, f),
+                    TraitMember::AssocTy(ty_decl) => walk_ty_definition_attributes(ty_decl, f),
+                }
+            }
+        }
+        Declaration::Impl(impl_decl) => {
+            walk_attribute_list(&impl_decl.inner_attributes, f);
+            for member in &impl_decl.body_items {
+                match member {
+                    ImplMember::Method(function) => walk_function_attributes(function, f),
+                    ImplMember::Constant(constant) => walk_attribute_list(&constant.attributes, f),
+                    ImplMember::AssocTy(ty_decl) => walk_ty_definition_attributes(ty_decl, f),
+                }
+            }
+        }
+        Declaration::ExternBlock(extern_decl) => {
+            walk_attribute_list(&extern_decl.inner_attributes, f);
+            for member in &extern_decl.body_items {
+                match member {
+                    ExternMember::Function(function) => walk_function_attributes(function, f),
+                    ExternMember::Static(constant) => walk_attribute_list(&constant.attributes, f),
+                    ExternMember::Ty(ty_decl) => walk_ty_definition_attributes(ty_decl, f),
+                }
+            }
+        }
+        Declaration::Function(function) => walk_function_params_attributes(function, f),
+        Declaration::TyDefinition(_) => {}
+        Declaration::Constant(_) => {}
+        Declaration::Use(_) => {}
+        Declaration::MacroDef(_) => {}
+    }
+}
+
+fn walk_function_attributes(function: &Function, f: &mut dyn FnMut(&Attribute)) {
+    walk_attribute_list(&function.attributes, f);
+    walk_generic_params_attributes(function.generic_params.as_ref(), f);
+    walk_function_params_attributes(function, f);
+}
+
+fn walk_ty_definition_attributes(ty_decl: &TyDefinition, f: &mut dyn FnMut(&Attribute)) {
+    walk_attribute_list(&ty_decl.attributes, f);
+    walk_generic_params_attributes(ty_decl.generic_params.as_ref(), f);
+}
+
+fn walk_generic_params_attributes(
+    generic_params: Option<&GenericParamList>,
+    f: &mut dyn FnMut(&Attribute),
+) {
+    if let Some(generic_params) = generic_params {
+        for param in generic_params.params.items() {
+            walk_attribute_list(&param.attributes, f);
+        }
+    }
+}
+
+fn walk_function_params_attributes(function: &Function, f: &mut dyn FnMut(&Attribute)) {
+    for param in function.params.items() {
+        let attributes = match param {
+            FnParam::Receiver(param) => &param.attributes,
+            FnParam::Typed(param) => &param.attributes,
+        };
+        walk_attribute_list(attributes, f);
+    }
+}
+
+fn walk_fields_attributes(fields: &StructFields, f: &mut dyn FnMut(&Attribute)) {
+    for field in fields.fields_iter() {
+        walk_attribute_list(field.attributes(), f);
+    }
+}
+
+fn walk_attribute_list(attributes: &[Attribute], f: &mut dyn FnMut(&Attribute)) {
+    for attribute in attributes {
+        f(attribute);
+    }
+}
+
+/// Returns the value of a string literal, eg `hello "world"` for `"hello \"world\""`.
+///
+/// Returns None if the literal isn't a string literal.
+fn parse_string_literal(literal: &Literal) -> Option<String> {
+    let repr = literal.to_string();
+
+    // Raw string, eg r#"hello"#
+    if let Some(raw) = repr.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let raw = &raw[hashes..raw.len() - hashes];
+        return Some(raw.strip_prefix('"')?.strip_suffix('"')?.to_string());
+    }
+
+    let repr = repr.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::with_capacity(repr.len());
+    let mut chars = repr.chars();
+    loop {
+        let c = match chars.next() {
+            Some('\\') => match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                '0' => '\0',
+                '\\' => '\\',
+                '\'' => '\'',
+                '"' => '"',
+                'x' => {
+                    let code: String = chars.by_ref().take(2).collect();
+                    u8::from_str_radix(&code, 16).ok()? as char
+                }
+                'u' => {
+                    let code: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+                    std::char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+                }
+                '\n' => {
+                    // Line continuation, skip leading whitespace of next line
+                    let rest = chars.as_str().trim_start();
+                    chars = rest.chars();
+                    continue;
+                }
+                _ => return None,
+            },
+            Some(c) => c,
+            None => break,
+        };
+        value.push(c);
+    }
+    Some(value)
 }